@@ -0,0 +1,235 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Duration, NaiveDate};
+use rand::Rng;
+use crate::rng::{random, sim_rng};
+use crate::cat::{CatInfo, Gender};
+use crate::personality::Personality;
+use crate::race::Race;
+
+const ADOPTER_NAMES: [&str; 12] = [
+    "Famille Martin", "Famille Bernard", "Famille Thomas", "Famille Robert", "Famille Richard", "Famille Dubois",
+    "Claire", "Julien", "Sophie", "Antoine", "Camille", "Hugo",
+];
+
+/// Most adopters waiting at the same time.
+pub const MAX_ADOPTERS: usize = 5;
+/// Days after an adoption during which the cat may be returned.
+pub const TRIAL_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HomeType {
+    Apartment,
+    House,
+    HouseWithGarden,
+    Farm,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AgePreference {
+    Kitten,
+    Adult,
+    Senior,
+    Any,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Temperament {
+    Calm,
+    Active,
+    Any,
+}
+
+impl AgePreference {
+    fn matches(&self, age: u8) -> bool {
+        match self {
+            AgePreference::Kitten => age <= 1,
+            AgePreference::Adult => (2..=10).contains(&age),
+            AgePreference::Senior => age >= 11,
+            AgePreference::Any => true,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Adopter {
+    pub id: u32,
+    pub name: &'static str,
+    pub home: HomeType,
+    pub other_pets: bool,
+    pub children: bool,
+    pub race: Option<Race>,
+    pub age: AgePreference,
+    pub gender: Option<Gender>,
+    pub temperament: Temperament,
+    /// Day the adopter gives up and leaves.
+    pub leaves: NaiveDate,
+}
+
+impl Adopter {
+    fn random(id: u32, today: NaiveDate) -> Self {
+        let mut rng = sim_rng();
+        Self {
+            id,
+            name: ADOPTER_NAMES[rng.gen_range(0..ADOPTER_NAMES.len())],
+            home: [HomeType::Apartment, HomeType::House, HomeType::HouseWithGarden, HomeType::Farm][rng.gen_range(0..4)],
+            other_pets: rng.gen_bool(0.3),
+            children: rng.gen_bool(0.4),
+            race: if rng.gen_bool(0.3) { Some(random()) } else { None },
+            age: [AgePreference::Kitten, AgePreference::Adult, AgePreference::Senior, AgePreference::Any][rng.gen_range(0..4)],
+            gender: if rng.gen_bool(0.3) { Some(Gender::get_random_gender()) } else { None },
+            temperament: [Temperament::Calm, Temperament::Active, Temperament::Any][rng.gen_range(0..3)],
+            leaves: today + Duration::days(rng.gen_range(5..=15)),
+        }
+    }
+
+    /// Scores from 0 to 100 how well `cat` fits this adopter.
+    pub fn compatibility(&self, cat: &CatInfo) -> u32 {
+        let mut score = 50i32;
+        score += match self.race {
+            Some(race) if race == cat.race => 20,
+            Some(_) => -10,
+            None => 5,
+        };
+        score += if self.age.matches(cat.age) { 15 } else { -15 };
+        score += match &self.gender {
+            Some(gender) if *gender == cat.gender => 10,
+            Some(_) => -10,
+            None => 0,
+        };
+
+        let active = cat.race.activity_level() >= 4 || cat.age <= 1;
+        score += match (self.temperament, active) {
+            (Temperament::Active, true) | (Temperament::Calm, false) => 15,
+            (Temperament::Any, _) => 5,
+            _ => -15,
+        };
+        score += match (self.home, active) {
+            (HomeType::Apartment, true) => -10,
+            (HomeType::HouseWithGarden | HomeType::Farm, true) => 10,
+            _ => 0,
+        };
+        if self.children && cat.age >= 11 {
+            score -= 5;
+        }
+        if self.other_pets && cat.age <= 1 {
+            score += 5;
+        }
+        if cat.sick {
+            score -= 20;
+        }
+        score += cat.adoptability();
+        if self.children && (cat.has(Personality::Aggressive) || cat.has(Personality::Shy)) {
+            score -= 10;
+        }
+        if self.temperament == Temperament::Active && cat.has(Personality::Playful) {
+            score += 5;
+        }
+        score.clamp(0, 100) as u32
+    }
+}
+
+impl Display for Adopter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\n- Home: {}\n- Other pets: {}\n- Children: {}\n- Race: {}\n- Age: {}\n- Gender: {}\n- Temperament: {}\n- Leaves: {}",
+            self.name,
+            match self.home {
+                HomeType::Apartment => "Apartment",
+                HomeType::House => "House",
+                HomeType::HouseWithGarden => "House with garden",
+                HomeType::Farm => "Farm",
+            },
+            if self.other_pets { "YES" } else { "NO" },
+            if self.children { "YES" } else { "NO" },
+            self.race.map_or("Any".to_string(), |race| race.to_string()),
+            match self.age {
+                AgePreference::Kitten => "Kitten",
+                AgePreference::Adult => "Adult",
+                AgePreference::Senior => "Senior",
+                AgePreference::Any => "Any",
+            },
+            self.gender.as_ref().map_or("Any".to_string(), |gender| gender.to_string()),
+            match self.temperament {
+                Temperament::Calm => "Calm",
+                Temperament::Active => "Active",
+                Temperament::Any => "Any",
+            },
+            self.leaves,
+        )
+    }
+}
+
+/// A cat rehomed recently enough to be brought back.
+pub struct Placement {
+    pub cat: CatInfo,
+    pub adopter: &'static str,
+    pub score: u32,
+    pub date: NaiveDate,
+}
+
+pub struct AdoptionCenter {
+    pub adopters: Vec<Adopter>,
+    pub placements: Vec<Placement>,
+    next_id: u32,
+}
+
+impl AdoptionCenter {
+    pub fn new() -> Self {
+        Self {
+            adopters: Vec::new(),
+            placements: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Rolls for a new adopter on this day, more likely with a good reputation.
+    pub fn arrive(&mut self, today: NaiveDate, reputation: f32) -> Option<&Adopter> {
+        if self.adopters.len() >= MAX_ADOPTERS || !sim_rng().gen_bool((0.3 * reputation / 50.0).min(1.0) as f64) {
+            return None;
+        }
+        self.adopters.push(Adopter::random(self.next_id, today));
+        self.next_id += 1;
+        self.adopters.last()
+    }
+
+    /// Sends away adopters who waited too long and returns their names.
+    pub fn expire(&mut self, today: NaiveDate) -> Vec<&'static str> {
+        let (gone, waiting): (Vec<Adopter>, Vec<Adopter>) = self.adopters.drain(..).partition(|adopter| adopter.leaves <= today);
+        self.adopters = waiting;
+        gone.into_iter().map(|adopter| adopter.name).collect()
+    }
+
+    /// Removes a waiting adopter, who is about to take a cat home.
+    pub fn take_adopter(&mut self, adopter_id: u32) -> Option<Adopter> {
+        let index = self.adopters.iter().position(|adopter| adopter.id == adopter_id)?;
+        Some(self.adopters.remove(index))
+    }
+
+    /// Hands `cat` over to `adopter` for a trial period and returns the compatibility score.
+    pub fn place(&mut self, adopter: Adopter, cat: CatInfo, today: NaiveDate) -> u32 {
+        let score = adopter.compatibility(&cat);
+        self.placements.push(Placement { cat, adopter: adopter.name, score, date: today });
+        score
+    }
+
+    /// Rolls for poorly matched cats being brought back and ends trial periods that are over.
+    pub fn returns(&mut self, today: NaiveDate) -> Vec<Placement> {
+        let mut rng = sim_rng();
+        let mut returned = vec![];
+        let mut kept = vec![];
+        for placement in self.placements.drain(..) {
+            if (today - placement.date).num_days() > TRIAL_DAYS {
+                continue;
+            }
+            let chance = (100 - placement.score) as f64 / 100.0 * 0.05;
+            if rng.gen_bool(chance) {
+                returned.push(placement);
+            } else {
+                kept.push(placement);
+            }
+        }
+        self.placements = kept;
+        returned
+    }
+}
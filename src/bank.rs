@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+
+/// How far below zero the balance may go before purchases are refused.
+pub const OVERDRAFT_LIMIT: i64 = 500;
+/// Monthly fee charged on a negative balance, as a fraction of the overdraft.
+pub const OVERDRAFT_RATE: f64 = 0.05;
+/// Most the bank lends in total.
+pub const MAX_DEBT: u64 = 20000;
+/// Consecutive months past the overdraft limit before an administrator takes over the shelter.
+pub const MONTHS_BEFORE_TAKEOVER: u32 = 3;
+
+/// Loan amounts offered by the bank, with their monthly interest rate.
+pub const LOAN_OFFERS: [(u64, f64); 3] = [(1000, 0.01), (5000, 0.015), (10000, 0.02)];
+
+#[derive(Clone, PartialEq)]
+pub struct Loan {
+    pub id: u32,
+    pub taken: NaiveDate,
+    pub principal: u64,
+    pub remaining: u64,
+    pub monthly_rate: f64,
+}
+
+impl Loan {
+    pub fn interest(&self) -> u64 {
+        (self.remaining as f64 * self.monthly_rate).ceil() as u64
+    }
+
+    /// Mandatory monthly repayment: a tenth of the principal, or what is left.
+    pub fn installment(&self) -> u64 {
+        (self.principal / 10).max(1).min(self.remaining)
+    }
+}
+
+pub struct Bank {
+    pub loans: Vec<Loan>,
+    /// Consecutive month ends spent past the overdraft limit.
+    pub months_in_default: u32,
+    next_loan_id: u32,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        Self {
+            loans: Vec::new(),
+            months_in_default: 0,
+            next_loan_id: 0,
+        }
+    }
+
+    pub fn debt(&self) -> u64 {
+        self.loans.iter().map(|loan| loan.remaining).sum()
+    }
+
+    pub fn can_borrow(&self, amount: u64) -> bool {
+        self.months_in_default == 0 && self.debt() + amount <= MAX_DEBT
+    }
+
+    pub fn borrow(&mut self, amount: u64, monthly_rate: f64, date: NaiveDate) -> &Loan {
+        self.loans.push(Loan {
+            id: self.next_loan_id,
+            taken: date,
+            principal: amount,
+            remaining: amount,
+            monthly_rate,
+        });
+        self.next_loan_id += 1;
+        self.loans.last().unwrap()
+    }
+
+    /// Lowers what is owed on a loan, dropping it once repaid, and returns the amount actually repaid.
+    pub fn repay(&mut self, loan_id: u32, amount: u64) -> u64 {
+        let Some(index) = self.loans.iter().position(|loan| loan.id == loan_id) else {
+            return 0;
+        };
+        let repaid = amount.min(self.loans[index].remaining);
+        self.loans[index].remaining -= repaid;
+        if self.loans[index].remaining == 0 {
+            self.loans.remove(index);
+        }
+        repaid
+    }
+
+    pub fn loan(&self, loan_id: u32) -> Option<&Loan> {
+        self.loans.iter().find(|loan| loan.id == loan_id)
+    }
+
+    /// Puts a loan back as it was, or drops it, when undoing or redoing a player action.
+    pub fn restore(&mut self, loan_id: u32, loan: Option<Loan>) {
+        self.loans.retain(|loan| loan.id != loan_id);
+        if let Some(loan) = loan {
+            let index = self.loans.iter().position(|other| other.id > loan_id).unwrap_or(self.loans.len());
+            self.loans.insert(index, loan);
+        }
+    }
+
+    /// Updates the default counter at the end of a month and returns whether the shelter is taken over.
+    pub fn close_month(&mut self, balance: i64) -> bool {
+        if balance < -OVERDRAFT_LIMIT {
+            self.months_in_default += 1;
+        } else {
+            self.months_in_default = 0;
+        }
+        self.months_in_default >= MONTHS_BEFORE_TAKEOVER
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn interest_is_rounded_up_on_what_remains() {
+        let mut bank = Bank::new();
+        let id = bank.borrow(1000, 0.015, today()).id;
+        assert_eq!(bank.loans[0].interest(), 15);
+        assert_eq!(bank.loans[0].installment(), 100);
+        assert_eq!(bank.repay(id, 999), 999);
+        assert_eq!(bank.loans[0].interest(), 1);
+        assert_eq!(bank.loans[0].installment(), 1);
+        assert_eq!(bank.repay(id, 50), 1);
+        assert!(bank.loans.is_empty());
+    }
+
+    #[test]
+    fn borrowing_is_capped_and_refused_in_default() {
+        let mut bank = Bank::new();
+        bank.borrow(MAX_DEBT - 1000, 0.01, today());
+        assert!(bank.can_borrow(1000));
+        assert!(!bank.can_borrow(5000));
+        bank.close_month(-OVERDRAFT_LIMIT - 1);
+        assert!(!bank.can_borrow(1000));
+    }
+
+    #[test]
+    fn takeover_after_consecutive_months_in_default() {
+        let mut bank = Bank::new();
+        for _ in 1..MONTHS_BEFORE_TAKEOVER {
+            assert!(!bank.close_month(-OVERDRAFT_LIMIT - 1));
+        }
+        assert!(!bank.close_month(-OVERDRAFT_LIMIT));
+        assert_eq!(bank.months_in_default, 0);
+        for _ in 1..MONTHS_BEFORE_TAKEOVER {
+            assert!(!bank.close_month(-OVERDRAFT_LIMIT - 1));
+        }
+        assert!(bank.close_month(-OVERDRAFT_LIMIT - 1));
+    }
+}
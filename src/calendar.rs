@@ -0,0 +1,149 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// Simulated hours elapsed on every simulation tick.
+pub const HOURS_PER_TICK: i64 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn of(date: NaiveDate) -> Self {
+        match date.month() {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// How many more cats come in than usual; spring is kitten season.
+    pub fn intake_factor(&self) -> f64 {
+        match self {
+            Season::Spring => 2.5,
+            Season::Summer => 1.5,
+            Season::Autumn => 1.0,
+            Season::Winter => 0.6,
+        }
+    }
+
+    /// Chance that an intake is a whole litter of kittens.
+    pub fn litter_chance(&self) -> f64 {
+        match self {
+            Season::Spring => 0.5,
+            Season::Summer => 0.3,
+            Season::Autumn | Season::Winter => 0.1,
+        }
+    }
+
+    /// Daily cost of heating the rooms.
+    pub fn heating(&self) -> u64 {
+        match self {
+            Season::Winter => 30,
+            Season::Autumn => 10,
+            Season::Spring => 5,
+            Season::Summer => 0,
+        }
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Season::Spring => write!(f, "Printemps"),
+            Season::Summer => write!(f, "Été"),
+            Season::Autumn => write!(f, "Automne"),
+            Season::Winter => write!(f, "Hiver"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Night,
+    Dawn,
+    Day,
+    Dusk,
+}
+
+impl TimeOfDay {
+    pub fn of(date: NaiveDateTime) -> Self {
+        match date.hour() {
+            5..=7 => TimeOfDay::Dawn,
+            8..=17 => TimeOfDay::Day,
+            18..=21 => TimeOfDay::Dusk,
+            _ => TimeOfDay::Night,
+        }
+    }
+
+    /// Cats are crepuscular: most active at dawn and dusk, napping through the day and sleeping at night.
+    pub fn activity(&self) -> f32 {
+        match self {
+            TimeOfDay::Dawn | TimeOfDay::Dusk => 1.5,
+            TimeOfDay::Day => 1.0,
+            TimeOfDay::Night => 0.5,
+        }
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeOfDay::Night => write!(f, "Nuit"),
+            TimeOfDay::Dawn => write!(f, "Aube"),
+            TimeOfDay::Day => write!(f, "Jour"),
+            TimeOfDay::Dusk => write!(f, "Crépuscule"),
+        }
+    }
+}
+
+pub struct Rollover {
+    pub day: bool,
+    pub month: bool,
+    pub season: bool,
+}
+
+pub struct Calendar {
+    pub now: NaiveDateTime,
+}
+
+impl Calendar {
+    /// Starts the simulation at 8h on `start`.
+    pub fn new(start: NaiveDate) -> Self {
+        Self {
+            now: start.and_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()),
+        }
+    }
+
+    /// Moves the calendar one tick forward and reports which periods started.
+    pub fn advance(&mut self) -> Rollover {
+        let day = self.date();
+        self.now += Duration::hours(HOURS_PER_TICK);
+        Rollover {
+            day: self.date() != day,
+            month: month_key(self.date()) != month_key(day),
+            season: Season::of(self.date()) != Season::of(day),
+        }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.now.date()
+    }
+
+    pub fn season(&self) -> Season {
+        Season::of(self.date())
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::of(self.now)
+    }
+}
+
+pub fn month_key(date: NaiveDate) -> (i32, u32) {
+    (date.year(), date.month())
+}
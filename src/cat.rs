@@ -1,32 +1,58 @@
 use std::cmp::PartialEq;
+use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::path::PathBuf;
-use chrono::{Datelike, DateTime, Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
 use egui_toast::ToastKind;
-use rand::{random, Rng, thread_rng};
+use rand::Rng;
+use crate::rng::{random, sim_rng};
 use crate::{bool_state};
+use crate::calendar::TimeOfDay;
 use crate::cat::Gender::{Female, Male};
 use crate::cat_name::{GENDER_FEMALE, GENDER_MALE};
 use crate::color::ColorType;
-use crate::inventory::Eat;
+use crate::inventory::{FoodKind, Inventory, Supply};
+use crate::personality::{self, Personality};
 use crate::race::Race;
+use crate::toy::ToyKind;
+use crate::room::DIRTY_LITTER;
 
-pub fn get_cat_image() -> String {
+/// Picks a picture for a new cat, or none when there is no picture to pick from.
+/// Pictures are only shown, so they don't take random numbers from the simulation.
+pub fn get_cat_image() -> Option<String> {
     let mut tmp = vec![];
     let path = PathBuf::from("cat pngs");
-    for x in fs::read_dir(&path).unwrap() {
-        let entry = x.ok();
-        let file_path = entry.unwrap().path();
+    for x in fs::read_dir(&path).ok()? {
+        let Ok(entry) = x else {
+            continue;
+        };
+        let file_path = entry.path();
 
         if file_path.is_file(){
             tmp.push(file_path);
         }
     }
-    tmp.get(thread_rng().gen_range(0..tmp.len())).unwrap().to_str().unwrap().to_string()
+    if tmp.is_empty() {
+        return None;
+    }
+    tmp[rand::thread_rng().gen_range(0..tmp.len())].to_str().map(str::to_string)
 }
 
 
+/// Energy under which a cat accepts to be put to rest.
+pub const REST_ENERGY: f32 = 80.0;
+/// Energy a cat needs to play.
+pub const PLAY_ENERGY: f32 = 20.0;
+/// Energy both cats need to mate.
+pub const MATE_ENERGY: f32 = 30.0;
+/// Coat condition under which the fur is matted and uncomfortable.
+pub const MATTED_COAT: f32 = 30.0;
+
+/// Number of simulation ticks kept in each cat's history.
+pub const HISTORY_LEN: usize = 500;
+
+
 #[derive(Eq, Default, PartialEq, Clone)]
 pub enum Gender {
     #[default]
@@ -51,18 +77,70 @@ impl Gender {
     pub fn get_random_name_and_gender() -> (&'static str, Gender) {
         let gender = Self::get_random_gender();
         match gender {
-            Male => {(GENDER_MALE[thread_rng().gen_range(0..GENDER_MALE.len())], gender)}
-            Female => {(GENDER_FEMALE[thread_rng().gen_range(0..GENDER_FEMALE.len())], gender)}
+            Male => {(GENDER_MALE[sim_rng().gen_range(0..GENDER_MALE.len())], gender)}
+            Female => {(GENDER_FEMALE[sim_rng().gen_range(0..GENDER_FEMALE.len())], gender)}
+        }
+    }
+}
+
+/// How the cat came to the shelter.
+#[derive(Clone, Copy)]
+pub enum Origin {
+    Unknown,
+    Born,
+    Stray,
+    Surrender(&'static str),
+    Litter,
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Unknown => write!(f, "Unknown"),
+            Origin::Born => write!(f, "Born at the shelter"),
+            Origin::Stray => write!(f, "Found as a stray"),
+            Origin::Surrender(reason) => write!(f, "Surrendered ({})", reason),
+            Origin::Litter => write!(f, "Dropped off with its litter"),
         }
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct CatRecord {
+    pub date: NaiveDateTime,
+    pub weight: f32,
+    pub health: f32,
+    pub food: f32,
+    pub mood: f32,
+    pub energy: f32,
+    pub coat: f32,
+}
+
+/// The parts of a cat player actions can change, compared to undo and redo them.
+#[derive(Clone, PartialEq)]
+pub struct CatState {
+    pub name: String,
+    pub age: u8,
+    pub weight: f32,
+    pub sleep: bool,
+    pub health: f32,
+    pub food: f32,
+    pub sick: bool,
+    pub room: usize,
+    pub stress: f32,
+    pub mood: f32,
+    pub energy: f32,
+    pub coat: f32,
+}
+
 #[derive(Clone)]
 pub struct CatInfo {
-    pub cat_image_byte: String,
+    /// Given by the shelter when the cat is admitted.
+    pub id: u64,
+    pub cat_image_byte: Option<String>,
     pub arrived_date: NaiveDate,
     pub bd_date: NaiveDate,
-    pub name: &'static str,
+    pub name: String,
     pub age: u8,
     pub color_type: ColorType,
     pub race: Race,
@@ -71,18 +149,31 @@ pub struct CatInfo {
     pub health: f32,
     pub food: f32,
     pub gender: Gender,
-    pub last_updated: DateTime<Local>,
+    pub sick: bool,
+    pub origin: Origin,
+    pub room: usize,
+    pub stress: f32,
+    pub quarantine_until: Option<NaiveDate>,
+    /// Happiness, from 0 (miserable) to 100 (content).
+    pub mood: f32,
+    pub personality: Vec<Personality>,
+    /// Rest left before the cat needs to sleep, from 0 (exhausted) to 100.
+    pub energy: f32,
+    /// Coat condition, from 0 (badly matted) to 100 (freshly brushed).
+    pub coat: f32,
+    /// Simulated day up to which birthdays have been counted in `age`.
+    pub last_updated: NaiveDate,
+    pub history: VecDeque<CatRecord>,
 }
 
 fn generate_random_date_in_range(start_date: NaiveDate, end_date: NaiveDate) -> NaiveDate {
-    let mut rng = thread_rng();
+    let mut rng = sim_rng();
     let days_range = (end_date - start_date).num_days();
     let random_days = rng.gen_range(0..=days_range);
     start_date + Duration::days(random_days)
 }
 
-fn generate_dates() -> (NaiveDate, NaiveDate) {
-    let today = Local::now().naive_utc().date();
+fn generate_dates(today: NaiveDate) -> (NaiveDate, NaiveDate) {
     let earliest_birth_date = NaiveDate::from_ymd_opt(2010, 1, 1).unwrap();
     let earliest_arrival_date = NaiveDate::from_ymd_opt(2015, 1, 1).unwrap();
 
@@ -104,103 +195,264 @@ fn calculate_age(birth_date: NaiveDate, current_date: NaiveDate) -> u8 {
 
 impl CatInfo {
 
-    pub(crate) fn new_cat() -> Self{
+    pub(crate) fn new_cat(today: NaiveDate) -> Self{
         let (name, gender) = Gender::get_random_name_and_gender();
-        let (birth_date, arrival_date) = generate_dates();
+        let (birth_date, arrival_date) = generate_dates(today);
         Self{
+            id: 0,
             cat_image_byte: get_cat_image(),
             arrived_date: arrival_date,
             bd_date: birth_date,
-            name,
-            age: calculate_age(birth_date, Local::now().naive_utc().date()),
+            name: name.to_string(),
+            age: calculate_age(birth_date, today),
             color_type: random(),
             race: random(),
-            weight: thread_rng().gen_range(0.5..7.0),
+            weight: sim_rng().gen_range(0.5..7.0),
             sleep: false,
             health: 100.0,
             food: 100.0,
             gender,
-            last_updated: Local::now(),
+            sick: false,
+            origin: Origin::Unknown,
+            room: 0,
+            stress: 0.0,
+            quarantine_until: None,
+            mood: 70.0,
+            personality: personality::random_traits(),
+            energy: 100.0,
+            coat: 100.0,
+            last_updated: today,
+            history: VecDeque::new(),
         }
     }
 
-    pub(crate) fn spawn_new_cat(nb_cat: u8) -> Vec<Self> {
+    /// A cat born on `bd_date` arriving at the shelter on `today`.
+    pub(crate) fn intake(origin: Origin, bd_date: NaiveDate, today: NaiveDate) -> Self {
+        Self {
+            arrived_date: today,
+            bd_date,
+            age: calculate_age(bd_date, today),
+            origin,
+            ..Self::new_cat(today)
+        }
+    }
+
+    pub(crate) fn spawn_new_cat(nb_cat: u8, today: NaiveDate) -> Vec<Self> {
         let mut cat_vec = Vec::new();
 
         for _ in 0..nb_cat {
             let color: ColorType = random();
             let race: Race = random();
             let sleep = random();
-            let health = thread_rng().gen_range(10.0..100.0);
+            let health = sim_rng().gen_range(10.0..100.0);
             let (name, gender) = Gender::get_random_name_and_gender();
-            let (birth_date, arrival_date) = generate_dates();
+            let (birth_date, arrival_date) = generate_dates(today);
             cat_vec.push(CatInfo {
+                id: 0,
                 cat_image_byte: get_cat_image(),
                 arrived_date: arrival_date,
                 bd_date: birth_date,
-                name,
-                age: calculate_age(birth_date, Local::now().naive_utc().date()),
+                name: name.to_string(),
+                age: calculate_age(birth_date, today),
                 color_type: color,
                 race,
-                weight: thread_rng().gen_range(1.5..7.0),
+                weight: sim_rng().gen_range(1.5..7.0),
                 sleep,
                 health,
                 food: 100.0,
                 gender,
-                last_updated: Local::now(),
+                sick: false,
+                origin: Origin::Unknown,
+                room: 0,
+                stress: 0.0,
+                quarantine_until: None,
+                mood: 70.0,
+                personality: personality::random_traits(),
+                energy: 100.0,
+                coat: 100.0,
+                last_updated: today,
+                history: VecDeque::new(),
             });
         }
         cat_vec
     }
 
-    pub(crate) fn feed(&mut self, weight: f32, health: f32, option: &mut Vec<Eat>) -> (String, ToastKind) {
+    pub(crate) fn feed(&mut self, kind: FoodKind, inventory: &mut Inventory, today: NaiveDate) -> (String, ToastKind) {
+        if self.sleep {
+            return (format!("{} dort et ne peut pas manger.", self.name), ToastKind::Warning);
+        }
+        let Some(portion) = inventory.take(kind, today) else {
+            return (format!("Not Enough {}", kind), ToastKind::Warning);
+        };
+        if portion.spoiled {
+            self.sick = true;
+            self.health = (self.health - 15.0).max(0.0);
+            return (format!("{} a mangé du {} avarié et est malade. Santé: {:.1}", self.name, kind, self.health), ToastKind::Error);
+        }
 
+        let profile = kind.profile();
+        let unsuitable = kind.unsuitable_for(self);
+        let factor = if unsuitable.is_some() { 0.5 } else { 1.0 };
+        self.weight += profile.weight * factor;
+        self.health += profile.health * factor;
+        self.food += profile.food * factor;
+
+        let message = format!("{} a été nourri ({}). Nouveau poids: {:.1} kg, Santé: {}, Nourriture: {:.1}", self.name, kind, self.weight, self.health, self.food);
+        match unsuitable {
+            Some(reason) => (format!("{}\n{}: {}", message, kind, reason), ToastKind::Info),
+            None => (message, ToastKind::Success),
+        }
+    }
 
-            if !self.sleep {
-                if let Some(food) = option.pop(){
-                self.weight += weight; // 0.1
-                self.health += health; // 5
-                self.food += food.food_value;  // Augmente la nourriture
-                (format!("{} a été nourri. Nouveau poids: {:.1} kg, Santé: {}, Nourriture: {:.1}", self.name, self.weight, self.health, self.food),  ToastKind::Success)
-                }else {
-                    ("Not Enough Food".to_string(), ToastKind::Warning)
-                }
-            }else {
-                (format!("{} dort et ne peut pas manger.", self.name), ToastKind::Warning)
-            }
+    pub(crate) fn has(&self, personality: Personality) -> bool {
+        self.personality.contains(&personality)
+    }
 
+    fn adjust_mood(&mut self, delta: f32) {
+        self.mood = (self.mood + delta).clamp(0.0, 100.0);
+    }
 
+    /// A play session with a toy from the inventory; how much the cat gets out of it depends on its preference.
+    pub(crate) fn play(&mut self, kind: ToyKind, inventory: &mut Inventory) -> (String, ToastKind) {
+        if self.sleep {
+            return (format!("{} dort et ne peut pas jouer.", self.name), ToastKind::Warning);
+        }
+        if self.energy < PLAY_ENERGY {
+            return (format!("{} est trop fatigué(e) pour jouer. Énergie: {:.0}", self.name, self.energy), ToastKind::Warning);
+        }
+        let Some(broke) = inventory.wear_toy(kind) else {
+            return (format!("No {} left", kind), ToastKind::Warning);
+        };
+        let worn = if broke { format!("\n{} is worn out", kind) } else { String::new() };
+        if self.has(Personality::Aggressive) && sim_rng().gen_bool(0.2) {
+            self.adjust_mood(-5.0);
+            return (format!("{} a griffé pendant le jeu. Humeur: {:.0}{}", self.name, self.mood, worn), ToastKind::Warning);
+        }
 
+        let profile = kind.profile();
+        let preference = kind.preference(self);
+        self.weight -= profile.weight * preference;
+        self.health = (self.health + preference).min(100.0);
+        self.energy = (self.energy - profile.energy * preference).max(0.0);
+        self.stress = (self.stress - profile.stress * preference).max(0.0);
+        self.adjust_mood(profile.mood * preference);
+        let message = format!("{} a joué ({}). Nouveau poids: {:.1} kg, Santé: {:.0}, Humeur: {:.0}, Énergie: {:.0}{}", self.name, kind, self.weight, self.health, self.mood, self.energy, worn);
+        if preference < 0.6 {
+            return (format!("{} s'intéresse peu à ce jouet.\n{}", self.name, message), ToastKind::Info);
+        }
+        (message, ToastKind::Success)
     }
 
-    pub(crate) fn play(&mut self, weight: f32, health: f32) -> (String, ToastKind) {
-        if !self.sleep {
-            self.weight -= weight; // 0.05
-            self.health += health; // 2
-            (format!("{} a joué. Nouveau poids: {:.1} kg, Santé: {}", self.name, self.weight, self.health), ToastKind::Success)
-        } else {
-            (format!("{} dort et ne peut pas jouer.", self.name), ToastKind::Warning)
+    /// Handling: cuddly cats love it, shy and aggressive ones would rather be left alone.
+    pub(crate) fn pet(&mut self) -> (String, ToastKind) {
+        if self.sleep {
+            return (format!("{} dort.", self.name), ToastKind::Warning);
+        }
+        if self.has(Personality::Aggressive) && sim_rng().gen_bool(0.3) {
+            self.adjust_mood(-5.0);
+            return (format!("{} a mordu ! Humeur: {:.0}", self.name, self.mood), ToastKind::Warning);
+        }
+        if self.has(Personality::Shy) {
+            self.adjust_mood(-5.0);
+            self.stress = (self.stress + 5.0).min(100.0);
+            return (format!("{} s'est caché(e). Humeur: {:.0}, Stress: {:.0}", self.name, self.mood, self.stress), ToastKind::Warning);
+        }
+        self.adjust_mood(if self.has(Personality::Cuddly) { 15.0 } else { 8.0 });
+        (format!("{} ronronne. Humeur: {:.0}", self.name, self.mood), ToastKind::Success)
+    }
+
+    /// Compatibility points the cat's mood and personality add to or remove from any adoption match.
+    pub(crate) fn adoptability(&self) -> i32 {
+        let mut score = (self.mood / 10.0) as i32 - 5;
+        for personality in &self.personality {
+            score += match personality {
+                Personality::Cuddly => 5,
+                Personality::Playful => 3,
+                Personality::Lazy => 0,
+                Personality::Shy => -5,
+                Personality::Aggressive => -15,
+            };
+        }
+        score
+    }
+
+    /// Suggests a nap; the cat only lies down if it is tired enough.
+    pub(crate) fn let_rest(&mut self) -> (String, ToastKind) {
+        if self.sleep {
+            return (format!("{} dort déjà.", self.name), ToastKind::Info);
+        }
+        if self.energy >= REST_ENERGY {
+            return (format!("{} n'a pas sommeil. Énergie: {:.0}", self.name, self.energy), ToastKind::Warning);
         }
+        self.sleep = true;
+        (format!("{} fait maintenant dodo. Énergie: {:.0}", self.name, self.energy), ToastKind::Success)
     }
 
-    pub(crate) fn toggle_sleep(&mut self, health: f32) -> (String, ToastKind) {
-        self.sleep = !self.sleep;
+    /// Quiet company from a volunteer: calms every cat, and helps shy ones come out of their shell.
+    pub(crate) fn socialise(&mut self) -> (String, ToastKind) {
         if self.sleep {
-            self.health += health; // 10
-            (format!("{} fait maintenant dodo. Santé: {}", self.name, self.health), ToastKind::Success)
+            return (format!("{} dort.", self.name), ToastKind::Warning);
+        }
+        let calm = if self.has(Personality::Shy) { 8.0 } else { 4.0 };
+        self.stress = (self.stress - calm).max(0.0);
+        self.adjust_mood(if self.has(Personality::Aggressive) { 1.0 } else { 4.0 });
+        (format!("{} a eu de la compagnie. Stress: {:.0}, Humeur: {:.0}", self.name, self.stress, self.mood), ToastKind::Success)
+    }
+
+    /// Brushing with a brush from the inventory; matted fur needs detangler, or the brush pulls on the mats.
+    pub(crate) fn groom(&mut self, inventory: &mut Inventory) -> (String, ToastKind) {
+        if self.sleep {
+            return (format!("{} dort.", self.name), ToastKind::Warning);
+        }
+        if inventory.supply_count(Supply::Brush) == 0 {
+            return (format!("No {} left", Supply::Brush), ToastKind::Warning);
+        }
+        if self.coat < MATTED_COAT && !inventory.take_supply(Supply::Detangler, 1) {
+            self.coat += 15.0;
+            self.stress = (self.stress + 5.0).min(100.0);
+            self.adjust_mood(-5.0);
+            return (format!("{} a des nœuds, le brossage sans démêlant fait mal. Pelage: {:.0}, Humeur: {:.0}", self.name, self.coat, self.mood), ToastKind::Warning);
+        }
+
+        self.coat = (self.coat + 50.0).min(100.0);
+        self.stress = (self.stress - 2.0).max(0.0);
+        self.adjust_mood(if self.has(Personality::Cuddly) { 8.0 } else { 3.0 });
+        (format!("{} a été brossé(e). Pelage: {:.0}, Humeur: {:.0}", self.name, self.coat, self.mood), ToastKind::Success)
+    }
+
+    /// Kittens and seniors sleep more: they tire faster and go to bed earlier.
+    fn sleepiness(&self) -> f32 {
+        if self.age <= 1 || self.age >= 11 { 1.5 } else { 1.0 }
+    }
+
+    /// Falls asleep when tired or at night, and wakes up rested during the day.
+    /// Cats burn more energy at dawn and dusk, nap through the day and sleep most of the night.
+    fn sleep_cycle(&mut self, date: NaiveDateTime) {
+        let activity = TimeOfDay::of(date).activity();
+        let rested = if self.sleepiness() > 1.0 { 80.0 } else { 60.0 };
+        if self.sleep {
+            self.energy = (self.energy + 25.0 / self.sleepiness()).min(100.0);
+            if self.energy >= 100.0 || self.energy >= rested / activity {
+                self.sleep = false;
+            }
         } else {
-            (format!("{} est maintenant réveillé.", self.name), ToastKind::Success)
+            self.energy = (self.energy - 8.0 * self.sleepiness() * activity).max(0.0);
+            if self.energy <= 35.0 * self.sleepiness() / activity {
+                self.sleep = true;
+            }
         }
     }
 
-    pub(crate) fn mate(&self, other: &Self) -> Result<Self, String> {
+    pub(crate) fn mate(&self, other: &Self, today: NaiveDate) -> Result<Self, String> {
         let mut tmp = String::new();
 
-        if self.gender.eq(&other.gender) || self.sleep || other.sleep {
-            tmp.push_str(&*format!("\nCan't mate {} with {}\nbecause:", self.name, other.name));
+        if self.gender.eq(&other.gender) || self.sleep || other.sleep || self.energy < MATE_ENERGY || other.energy < MATE_ENERGY {
+            tmp.push_str(&format!("\nCan't mate {} with {}\nbecause:", self.name, other.name));
             if self.gender.eq(&other.gender) { tmp.push_str("\n- Same Sexe"); }
-            if self.sleep { tmp.push_str(&*format!("\n- {} sleep", self.name)); }
-            if other.sleep { tmp.push_str(&*format!("\n- {} sleep", other.name)); }
+            if self.sleep { tmp.push_str(&format!("\n- {} sleep", self.name)); }
+            if other.sleep { tmp.push_str(&format!("\n- {} sleep", other.name)); }
+            if self.energy < MATE_ENERGY { tmp.push_str(&format!("\n- {} tired", self.name)); }
+            if other.energy < MATE_ENERGY { tmp.push_str(&format!("\n- {} tired", other.name)); }
             return Err(tmp);
         }
 
@@ -208,12 +460,15 @@ impl CatInfo {
         let (name, gender) = Gender::get_random_name_and_gender();
         let color = if random() { self.color_type } else { other.color_type };
         let race = if random() { self.race } else { other.race };
+        let (mother, father) = if self.gender == Female { (self, other) } else { (other, self) };
+        let (mother, father) = (&mother.personality, &father.personality);
 
         Ok(CatInfo {
+            id: 0,
             cat_image_byte: get_cat_image(),
-            arrived_date: Local::now().naive_utc().date(),
-            bd_date: Local::now().naive_utc().date(),
-            name,
+            arrived_date: today,
+            bd_date: today,
+            name: name.to_string(),
             age: 1,
             color_type: color,
             race,
@@ -222,23 +477,33 @@ impl CatInfo {
             health: 100.0,
             food: 100.0,
             gender,
-            last_updated: Local::now(),
+            sick: false,
+            origin: Origin::Born,
+            room: 0,
+            stress: 0.0,
+            quarantine_until: None,
+            mood: 80.0,
+            personality: personality::inherit(mother, father),
+            energy: 100.0,
+            coat: 100.0,
+            last_updated: today,
+            history: VecDeque::new(),
         })
     }
 
-    pub(crate) fn update(&mut self) -> Option<()> {
+    /// Advances the cat by one tick in a room whose litter boxes are filled to `litter`.
+    pub(crate) fn update(&mut self, date: NaiveDateTime, litter: f32) -> Option<()> {
 
         if self.age >= 20{
             return  None
         }
 
-        let now = Local::now();
-        let duration = now.signed_duration_since(self.last_updated);
-
-        if duration.num_minutes() >= 5 {
-            self.age += (duration.num_minutes() / 5) as u8;
-            self.health = (self.health - 5.0 * (duration.num_minutes() / 5) as f32).max(0.0);
-            self.last_updated = now;
+        let today = date.date();
+        let years = calculate_age(self.bd_date, today).saturating_sub(calculate_age(self.bd_date, self.last_updated));
+        self.last_updated = today;
+        if years > 0 {
+            self.age = self.age.saturating_add(years);
+            self.health = (self.health - 5.0 * years as f32).max(0.0);
             println!("{} a vieilli. Nouvel âge: {}, Santé: {}", self.name, self.age, self.health);
         }
 
@@ -246,6 +511,42 @@ impl CatInfo {
             return  None
         }
 
+        self.sleep_cycle(date);
+        self.adjust_mood((100.0 - self.stress - self.mood) * 0.05);
+        if self.food < 20.0 || self.sick {
+            self.adjust_mood(-2.0);
+        }
+
+        if litter > DIRTY_LITTER {
+            let dirt = (litter - DIRTY_LITTER) / (100.0 - DIRTY_LITTER);
+            self.health = (self.health - 2.0 * dirt).max(0.0);
+            self.stress = (self.stress + 3.0).min(100.0);
+            self.adjust_mood(-3.0);
+            if !self.sick && sim_rng().gen_bool((0.02 * dirt) as f64) {
+                self.sick = true;
+            }
+        }
+
+        self.coat = (self.coat - self.race.coat_wear()).max(0.0);
+        if self.coat < MATTED_COAT {
+            self.stress = (self.stress + 2.0).min(100.0);
+            self.adjust_mood(-2.0);
+            if self.coat < 10.0 {
+                self.health = (self.health - 1.0).max(0.0);
+            }
+        }
+
+        if self.stress > 50.0 {
+            self.health = (self.health - 1.0).max(0.0);
+        }
+
+        if self.sick {
+            self.health = (self.health - 3.0).max(0.0);
+            if sim_rng().gen_bool(0.1) {
+                self.sick = false;
+            }
+        }
+
         if self.food > 0.0 {
             self.food -= 0.5;
         } else {
@@ -253,15 +554,70 @@ impl CatInfo {
         }
 
         if self.health > 0.0 {
-            Some(self.health = (self.health - 2.0).max(0.0))
+            self.health = (self.health - 2.0).max(0.0);
+            self.record_history(date);
+            Some(())
         }else {
             None
         }
     }
 
+    pub(crate) fn state(&self) -> CatState {
+        CatState {
+            name: self.name.clone(),
+            age: self.age,
+            weight: self.weight,
+            sleep: self.sleep,
+            health: self.health,
+            food: self.food,
+            sick: self.sick,
+            room: self.room,
+            stress: self.stress,
+            mood: self.mood,
+            energy: self.energy,
+            coat: self.coat,
+        }
+    }
+
+    /// Applies the change from `from` to `to` on top of the current state: stats move by the same
+    /// amount so that ticks since then are kept, the rest is set to `to`.
+    pub(crate) fn apply_change(&mut self, from: &CatState, to: &CatState) {
+        self.name = to.name.clone();
+        self.age = if to.age >= from.age { self.age.saturating_add(to.age - from.age) } else { self.age.saturating_sub(from.age - to.age) };
+        self.weight = (self.weight + to.weight - from.weight).max(0.0);
+        self.sleep = to.sleep;
+        self.health = (self.health + to.health - from.health).max(0.0);
+        self.food = (self.food + to.food - from.food).max(0.0);
+        self.sick = to.sick;
+        self.room = to.room;
+        self.stress = (self.stress + to.stress - from.stress).clamp(0.0, 100.0);
+        self.adjust_mood(to.mood - from.mood);
+        self.energy = (self.energy + to.energy - from.energy).clamp(0.0, 100.0);
+        self.coat = (self.coat + to.coat - from.coat).clamp(0.0, 100.0);
+    }
+
+    pub(crate) fn in_quarantine(&self, today: NaiveDate) -> bool {
+        self.quarantine_until.is_some_and(|until| today < until)
+    }
+
+    fn record_history(&mut self, date: NaiveDateTime) {
+        if self.history.len() >= HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(CatRecord {
+            date,
+            weight: self.weight,
+            health: self.health,
+            food: self.food,
+            mood: self.mood,
+            energy: self.energy,
+            coat: self.coat,
+        });
+    }
+
     pub(crate) fn minimal_info(&self)  -> String{
-        format!("Name: {}\n- Genre: {}\n- Age: {}\n- Sleep: {}\n- Health: {:.2}\n- Food: {:.2}",
-                self.name, self.gender, self.age, bool_state!("YES", "NO", self.sleep), self.health, self.food)
+        format!("Name: {}\n- Genre: {}\n- Age: {}\n- Sleep: {}\n- Energy: {:.0}\n- Health: {:.2}{}\n- Food: {:.2}\n- Mood: {:.0}",
+                self.name, self.gender, self.age, bool_state!("YES", "NO", self.sleep), self.energy, self.health, bool_state!(" (sick)", "", self.sick), self.food, self.mood)
     }
 
 }
@@ -270,8 +626,8 @@ impl Display for CatInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Name: {}\n- Age: {} an(s)\n- Color: {}\n- Race: {}\n- Weight: {:.2} kg\n- Sleep: {}\n- Health: {:.2}\n- Food: {:.2}\n- Sexe: {}\n- Entrance: {}\n- Bd: {}",
-            self.name, self.age, self.color_type, self.race, self.weight, bool_state!("YES", "NO", self.sleep), self.health, self.food, self.gender, self.arrived_date, self.bd_date,
+            "Name: {}\n- Age: {} an(s)\n- Color: {}\n- Race: {}\n- Weight: {:.2} kg\n- Sleep: {}\n- Energy: {:.0}\n- Health: {:.2}\n- Sick: {}\n- Stress: {:.0}\n- Mood: {:.0}\n- Coat: {:.0}\n- Traits: {}\n- Food: {:.2}\n- Sexe: {}\n- Origin: {}\n- Entrance: {}\n- Bd: {}",
+            self.name, self.age, self.color_type, self.race, self.weight, bool_state!("YES", "NO", self.sleep), self.energy, self.health, bool_state!("YES", "NO", self.sick), self.stress, self.mood, self.coat, personality::format_traits(&self.personality), self.food, self.gender, self.origin, self.arrived_date, self.bd_date,
         )
     }
 }
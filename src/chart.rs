@@ -0,0 +1,81 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+use chrono::{NaiveDateTime, Timelike};
+use eframe::egui::{pos2, vec2, Align2, Color32, FontId, Sense, Shape, Stroke, Ui, Vec2};
+
+/// Draws `values` as a line chart filling the available width, scaled between the series min and max.
+pub fn line_chart(ui: &mut Ui, label: &str, values: &[f32], color: Color32) {
+    ui.label(label);
+    let (response, painter) = ui.allocate_painter(vec2(ui.available_width(), 120.0), Sense::hover());
+    let rect = response.rect;
+    painter.rect_stroke(rect, 4.0, Stroke::new(1.0, Color32::DARK_GRAY));
+
+    if values.len() < 2 {
+        painter.text(rect.center(), Align2::CENTER_CENTER, "Not enough data", FontId::proportional(12.0), Color32::GRAY);
+        return;
+    }
+
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = if max - min > f32::EPSILON { max - min } else { 1.0 };
+    let step = rect.width() / (values.len() - 1) as f32;
+
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| pos2(rect.left() + i as f32 * step, rect.bottom() - (v - min) / range * rect.height()))
+        .collect();
+    painter.add(Shape::line(points, Stroke::new(2.0, color)));
+
+    let font = FontId::monospace(10.0);
+    painter.text(rect.left_top() + vec2(4.0, 2.0), Align2::LEFT_TOP, format!("{:.2}", max), font.clone(), Color32::GRAY);
+    painter.text(rect.left_bottom() + vec2(4.0, -2.0), Align2::LEFT_BOTTOM, format!("{:.2}", min), font, Color32::GRAY);
+
+    if let Some(hover) = response.hover_pos() {
+        let index = (((hover.x - rect.left()) / step).round() as usize).min(values.len() - 1);
+        response.on_hover_text(format!("#{}: {:.2}", index, values[index]));
+    }
+}
+
+/// Draws `center` surrounded by the cats it knows, linked by green (affinity) or red (conflict) edges.
+pub fn relationship_graph(ui: &mut Ui, center: &str, links: &[(String, f32)]) {
+    let (response, painter) = ui.allocate_painter(vec2(ui.available_width(), 220.0), Sense::hover());
+    let rect = response.rect;
+    let font = FontId::proportional(11.0);
+    if links.is_empty() {
+        painter.text(rect.center(), Align2::CENTER_CENTER, "No relationship yet", font, Color32::GRAY);
+        return;
+    }
+
+    let radius = rect.height() / 2.0 - 20.0;
+    for (index, (name, affinity)) in links.iter().enumerate() {
+        let angle = index as f32 / links.len() as f32 * TAU;
+        let position = rect.center() + Vec2::angled(angle) * radius;
+        let color = if *affinity >= 0.0 { Color32::LIGHT_GREEN } else { Color32::LIGHT_RED };
+        painter.line_segment([rect.center(), position], Stroke::new(1.0 + affinity.abs() / 25.0, color));
+        painter.circle_filled(position, 4.0, color);
+        painter.text(position + vec2(0.0, 6.0), Align2::CENTER_TOP, format!("{} ({:.0})", name, affinity), font.clone(), Color32::GRAY);
+    }
+    painter.circle_filled(rect.center(), 6.0, Color32::WHITE);
+    painter.text(rect.center() + vec2(0.0, -8.0), Align2::CENTER_BOTTOM, center, font, Color32::WHITE);
+}
+
+/// Draws a 24 hour clock face: dark at night, light during the day, with a single hand on the current hour.
+pub fn clock(ui: &mut Ui, now: NaiveDateTime) {
+    let (response, painter) = ui.allocate_painter(vec2(64.0, 64.0), Sense::hover());
+    let center = response.rect.center();
+    let radius = 28.0;
+    let night = !(6..20).contains(&now.hour());
+    painter.circle_filled(center, radius, if night { Color32::from_rgb(20, 30, 70) } else { Color32::from_rgb(120, 170, 220) });
+    painter.circle_stroke(center, radius, Stroke::new(1.0, Color32::GRAY));
+
+    let font = FontId::monospace(8.0);
+    for hour in [0, 6, 12, 18] {
+        let angle = hour as f32 / 24.0 * TAU - FRAC_PI_2;
+        painter.text(center + Vec2::angled(angle) * (radius - 7.0), Align2::CENTER_CENTER, hour.to_string(), font.clone(), Color32::WHITE);
+    }
+    let hours = now.hour() as f32 + now.minute() as f32 / 60.0;
+    let angle = hours / 24.0 * TAU - FRAC_PI_2;
+    painter.line_segment([center, center + Vec2::angled(angle) * (radius - 12.0)], Stroke::new(2.0, Color32::YELLOW));
+    painter.circle_filled(center, 2.0, Color32::YELLOW);
+    response.on_hover_text(now.format("%Hh%M").to_string());
+}
@@ -0,0 +1,114 @@
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::calendar::Season;
+use crate::cat::CatInfo;
+
+/// Daily rent of the shelter building.
+pub const RENT: u64 = 40;
+/// Daily water and power bill; heating comes on top depending on the season.
+pub const UTILITIES: u64 = 15;
+/// Daily upkeep of each cat (vaccines, bedding, ...).
+pub const UPKEEP_PER_CAT: u64 = 3;
+
+/// Running costs charged every simulated day, by label.
+pub fn daily_costs(cat_count: usize, season: Season) -> [(&'static str, u64); 4] {
+    [
+        ("Rent", RENT),
+        ("Utilities", UTILITIES),
+        ("Heating", season.heating()),
+        ("Cat upkeep", UPKEEP_PER_CAT * cat_count as u64),
+    ]
+}
+
+const SPONSORS: [&str; 10] = [
+    "Mme Dupont", "M. Martin", "Mme Bernard", "M. Petit", "Mme Durand",
+    "M. Leroy", "Mme Moreau", "M. Simon", "Mme Laurent", "M. Michel",
+];
+
+/// Base adoption fee before adjustments for age, breed and health.
+pub const BASE_ADOPTION_FEE: u64 = 100;
+
+/// Fee paid by the adopter of `cat`: kittens and popular breeds cost more, seniors and sick cats less.
+pub fn adoption_fee(cat: &CatInfo) -> u64 {
+    let mut fee = BASE_ADOPTION_FEE as f32 + cat.race.breed_premium() as f32;
+    if cat.age <= 1 {
+        fee *= 1.5;
+    } else if cat.age >= 11 {
+        fee *= 0.5;
+    }
+    fee *= (cat.health / 100.0).clamp(0.3, 1.0);
+    fee.round() as u64
+}
+
+/// A virtual sponsor paying a monthly amount for one cat.
+pub struct Sponsorship {
+    pub sponsor: &'static str,
+    pub cat_id: u64,
+    pub monthly: u64,
+}
+
+pub struct Income {
+    /// Public image of the shelter, from 0 to 100.
+    pub reputation: f32,
+    pub sponsorships: Vec<Sponsorship>,
+}
+
+impl Income {
+    pub fn new() -> Self {
+        Self {
+            reputation: 50.0,
+            sponsorships: Vec::new(),
+        }
+    }
+
+    /// Rolls for a donation on this tick, more likely and larger with a good reputation.
+    pub fn donation(&self) -> Option<u64> {
+        let mut rng = sim_rng();
+        if rng.gen_bool((self.reputation / 100.0 * 0.2) as f64) {
+            Some(rng.gen_range(10..=10 + (self.reputation * 2.0) as u64))
+        } else {
+            None
+        }
+    }
+
+    /// Rolls for a new sponsor on this day, picking a cat without one.
+    pub fn find_sponsor(&mut self, cats: &[CatInfo]) -> Option<&Sponsorship> {
+        let mut rng = sim_rng();
+        if !rng.gen_bool((self.reputation / 100.0 * 0.1) as f64) {
+            return None;
+        }
+        let candidates: Vec<&CatInfo> = cats.iter().filter(|cat| self.sponsor_of(cat.id).is_none()).collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let cat = candidates[rng.gen_range(0..candidates.len())];
+        self.sponsorships.push(Sponsorship {
+            sponsor: SPONSORS[rng.gen_range(0..SPONSORS.len())],
+            cat_id: cat.id,
+            monthly: rng.gen_range(2..=6) * 5,
+        });
+        self.sponsorships.last()
+    }
+
+    pub fn sponsor_of(&self, cat_id: u64) -> Option<&Sponsorship> {
+        self.sponsorships.iter().find(|sponsorship| sponsorship.cat_id == cat_id)
+    }
+
+    /// Ends the sponsorship of a cat leaving the shelter.
+    pub fn cat_left(&mut self, cat_id: u64) {
+        self.sponsorships.retain(|sponsorship| sponsorship.cat_id != cat_id);
+    }
+
+    /// Moves the reputation toward the average health of the cats, minus a penalty per death.
+    pub fn update_reputation(&mut self, cats: &[CatInfo], deaths: usize) {
+        if !cats.is_empty() {
+            let avg_health = cats.iter().map(|cat| cat.health).sum::<f32>() / cats.len() as f32;
+            self.reputation += (avg_health - self.reputation) * 0.1;
+        }
+        self.adjust_reputation(-3.0 * deaths as f32);
+    }
+
+    pub fn adjust_reputation(&mut self, delta: f32) {
+        self.reputation = (self.reputation + delta).clamp(0.0, 100.0);
+    }
+}
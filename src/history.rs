@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::bank::Loan;
+use crate::cat::{CatInfo, CatState};
+use crate::inventory::InventoryDelta;
+use crate::ledger::Category;
+
+/// Player actions kept for undo.
+pub const MAX_HISTORY: usize = 100;
+
+/// One thing a player action changed, with enough to reverse it and to apply it again.
+pub enum Change {
+    /// A cat's state before and after the action.
+    Cat { id: u64, before: CatState, after: CatState },
+    Inventory(InventoryDelta),
+    /// A cat that joined the shelter.
+    Admitted(Box<CatInfo>),
+    /// Money booked by the action; undoing books the opposite amount.
+    Money { amount: i64, category: Category, label: String, cat_id: Option<u64> },
+    Litter { room: usize, amount: f32 },
+    /// Portions eaten and kittens born, counted in the statistics of the month of `date`.
+    Stats { date: NaiveDate, food_used: u32, births: u32 },
+    /// A loan before and after the action, `None` when it did not exist.
+    Loan { id: u32, before: Option<Loan>, after: Option<Loan> },
+}
+
+pub struct Command {
+    pub label: String,
+    pub date: NaiveDateTime,
+    pub changes: Vec<Change>,
+}
+
+/// Actions that can be undone, oldest first, and the undone ones that can be redone, most recent last.
+pub struct History {
+    pub done: VecDeque<Command>,
+    pub undone: Vec<Command>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self { done: VecDeque::new(), undone: vec![] }
+    }
+
+    /// Records a new action, which makes the undone ones impossible to redo.
+    pub fn record(&mut self, command: Command) {
+        if command.changes.is_empty() {
+            return;
+        }
+        if self.done.len() >= MAX_HISTORY {
+            self.done.pop_front();
+        }
+        self.done.push_back(command);
+        self.undone.clear();
+    }
+}
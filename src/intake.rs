@@ -0,0 +1,94 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Duration, NaiveDate};
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::calendar::Season;
+use crate::cat::{CatInfo, Origin};
+
+const SURRENDER_REASONS: [&str; 6] = [
+    "déménagement", "allergie", "décès du propriétaire", "arrivée d'un bébé", "problèmes financiers", "manque de temps",
+];
+
+/// Chance of an intake event on any simulated day, before the seasonal factor.
+pub const DAILY_INTAKE_CHANCE: f64 = 0.15;
+
+#[derive(Clone, Copy)]
+pub enum IntakeKind {
+    Stray,
+    Surrender,
+    Litter,
+}
+
+impl Display for IntakeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntakeKind::Stray => write!(f, "Chat errant trouvé"),
+            IntakeKind::Surrender => write!(f, "Abandon par son propriétaire"),
+            IntakeKind::Litter => write!(f, "Portée déposée"),
+        }
+    }
+}
+
+pub struct Intake {
+    pub kind: IntakeKind,
+    pub cats: Vec<CatInfo>,
+}
+
+/// Rolls for cats arriving at the shelter on `today`; spring brings more cats and more litters.
+pub fn roll(today: NaiveDate) -> Option<Intake> {
+    let mut rng = sim_rng();
+    let season = Season::of(today);
+    if !rng.gen_bool((DAILY_INTAKE_CHANCE * season.intake_factor()).min(1.0)) {
+        return None;
+    }
+    Some(if rng.gen_bool(season.litter_chance()) {
+        litter(today)
+    } else if rng.gen_bool(0.6) {
+        stray(today)
+    } else {
+        surrender(today)
+    })
+}
+
+fn born_years_ago(today: NaiveDate, min: i64, max: i64) -> NaiveDate {
+    today - Duration::days(sim_rng().gen_range(min * 365..=max * 365))
+}
+
+/// A stray in poor condition: thin, hungry and often ill.
+fn stray(today: NaiveDate) -> Intake {
+    let mut rng = sim_rng();
+    let mut cat = CatInfo::intake(Origin::Stray, born_years_ago(today, 1, 12), today);
+    cat.health = rng.gen_range(20.0..60.0);
+    cat.food = rng.gen_range(10.0..50.0);
+    cat.weight = rng.gen_range(1.5..4.0);
+    cat.sick = rng.gen_bool(0.3);
+    cat.coat = rng.gen_range(10.0..60.0);
+    Intake { kind: IntakeKind::Stray, cats: vec![cat] }
+}
+
+/// An owned cat given up with a known reason, usually in good shape.
+fn surrender(today: NaiveDate) -> Intake {
+    let mut rng = sim_rng();
+    let reason = SURRENDER_REASONS[rng.gen_range(0..SURRENDER_REASONS.len())];
+    let mut cat = CatInfo::intake(Origin::Surrender(reason), born_years_ago(today, 1, 15), today);
+    cat.health = rng.gen_range(60.0..100.0);
+    cat.food = rng.gen_range(60.0..100.0);
+    cat.weight = rng.gen_range(3.0..7.0);
+    Intake { kind: IntakeKind::Surrender, cats: vec![cat] }
+}
+
+/// A few weeks old kittens left together.
+fn litter(today: NaiveDate) -> Intake {
+    let mut rng = sim_rng();
+    let bd_date = today - Duration::weeks(rng.gen_range(2..=8));
+    let cats = (0..rng.gen_range(2..=5))
+        .map(|_| {
+            let mut cat = CatInfo::intake(Origin::Litter, bd_date, today);
+            cat.health = rng.gen_range(50.0..90.0);
+            cat.food = rng.gen_range(30.0..70.0);
+            cat.weight = rng.gen_range(0.3..1.0);
+            cat
+        })
+        .collect();
+    Intake { kind: IntakeKind::Litter, cats }
+}
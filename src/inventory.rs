@@ -1,27 +1,511 @@
-pub struct Eat {
-    pub id: u32,
-    pub name: String,
-    pub food_value: f32,
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use chrono::{Duration, NaiveDate};
+use crate::cat::CatInfo;
+use crate::toy::{Toy, ToyKind};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FoodKind {
+    DryKibble,
+    WetPouch,
+    KittenFormula,
+    SeniorDiet,
+    RenalDiet,
+    Treat,
+}
+
+/// Effects of one portion of food on the cat eating it.
+pub struct FoodProfile {
+    pub food: f32,
+    pub health: f32,
+    pub weight: f32,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 6] = [
+        FoodKind::DryKibble,
+        FoodKind::WetPouch,
+        FoodKind::KittenFormula,
+        FoodKind::SeniorDiet,
+        FoodKind::RenalDiet,
+        FoodKind::Treat,
+    ];
+
+    /// Stable identifier of the food kind, also its stack index in the inventory.
+    pub fn id(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn profile(&self) -> FoodProfile {
+        let (food, health, weight) = match self {
+            FoodKind::DryKibble => (2.0, 5.0, 0.1),
+            FoodKind::WetPouch => (3.0, 6.0, 0.1),
+            FoodKind::KittenFormula => (3.0, 8.0, 0.15),
+            FoodKind::SeniorDiet => (2.0, 7.0, 0.05),
+            FoodKind::RenalDiet => (1.5, 10.0, -0.05),
+            FoodKind::Treat => (0.5, 1.0, 0.2),
+        };
+        FoodProfile { food, health, weight }
+    }
+
+    pub fn shelf_life(&self) -> Duration {
+        Duration::days(match self {
+            FoodKind::DryKibble => 180,
+            FoodKind::WetPouch => 30,
+            FoodKind::KittenFormula => 90,
+            FoodKind::SeniorDiet => 180,
+            FoodKind::RenalDiet => 120,
+            FoodKind::Treat => 365,
+        })
+    }
+
+    /// Returns why this food does not suit the cat, if it doesn't.
+    pub fn unsuitable_for(&self, cat: &CatInfo) -> Option<&'static str> {
+        match self {
+            FoodKind::KittenFormula if cat.age > 1 => Some("only for kittens"),
+            FoodKind::SeniorDiet if cat.age < 11 => Some("only for seniors"),
+            FoodKind::RenalDiet if cat.health >= 50.0 => Some("only for cats in poor health"),
+            FoodKind::DryKibble if cat.age == 0 => Some("too hard for a kitten"),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FoodKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FoodKind::DryKibble => write!(f, "Dry kibble"),
+            FoodKind::WetPouch => write!(f, "Wet pouch"),
+            FoodKind::KittenFormula => write!(f, "Kitten formula"),
+            FoodKind::SeniorDiet => write!(f, "Senior diet"),
+            FoodKind::RenalDiet => write!(f, "Renal diet"),
+            FoodKind::Treat => write!(f, "Treat"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Supply {
+    Litter,
+    Brush,
+    Detangler,
+}
+
+impl Supply {
+    pub const ALL: [Supply; 3] = [Supply::Litter, Supply::Brush, Supply::Detangler];
+
+    pub fn id(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Display for Supply {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Supply::Litter => write!(f, "Litter bag"),
+            Supply::Brush => write!(f, "Brush"),
+            Supply::Detangler => write!(f, "Detangling spray"),
+        }
+    }
+}
+
+/// Maximum quantity a single stack can hold.
+pub const STACK_CAPACITY: u32 = 5000;
+
+/// Batches expiring within this many days are flagged in the inventory view.
+pub const EXPIRING_SOON_DAYS: i64 = 7;
+
+/// Portions of one food kind bought on the same day.
+#[derive(Clone)]
+pub struct Batch {
+    pub purchased: NaiveDate,
+    pub expires: NaiveDate,
+    pub quantity: u32,
+}
+
+impl Batch {
+    pub fn is_spoiled(&self, today: NaiveDate) -> bool {
+        today >= self.expires
+    }
+
+    pub fn is_expiring_soon(&self, today: NaiveDate) -> bool {
+        !self.is_spoiled(today) && (self.expires - today).num_days() <= EXPIRING_SOON_DAYS
+    }
+}
+
+/// Every portion of one food kind held by the shelter, oldest batch first.
+#[derive(Clone)]
+pub struct Stack {
+    pub id: usize,
+    pub kind: FoodKind,
+    pub quantity: u32,
+    pub capacity: u32,
+    pub batches: VecDeque<Batch>,
+}
+
+/// Difference between two inventories, used to undo and redo player actions.
+pub struct InventoryDelta {
+    /// Portions added to (or removed from) each batch, identified by food kind and purchase date.
+    batches: Vec<(FoodKind, NaiveDate, i64)>,
+    supplies: Vec<i64>,
+    /// Toys, by kind and durability, that only exist after or only before.
+    toys_added: Vec<(ToyKind, u32)>,
+    toys_removed: Vec<(ToyKind, u32)>,
+}
+
+impl InventoryDelta {
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty() && self.supplies.iter().all(|delta| *delta == 0) && self.toys_added.is_empty() && self.toys_removed.is_empty()
+    }
+}
+
+/// Items of `a` missing from `b`, counting duplicates.
+fn missing<T: PartialEq + Copy>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut rest = b.to_vec();
+    let mut missing = vec![];
+    for item in a {
+        match rest.iter().position(|other| other == item) {
+            Some(index) => {
+                rest.swap_remove(index);
+            }
+            None => missing.push(*item),
+        }
+    }
+    missing
+}
+
+/// A portion taken out of the inventory.
+pub struct Portion {
+    pub spoiled: bool,
 }
 
+#[derive(Clone)]
 pub struct Inventory {
-    pub slot: Vec<Eat>,
+    stacks: Vec<Stack>,
+    supplies: Vec<u32>,
+    toys: Vec<Toy>,
 }
 
 impl Inventory {
     pub fn get_inventory() -> Self {
         Self{
-            slot: Vec::<Eat>::new(),
+            stacks: FoodKind::ALL.iter().map(|kind| Stack {
+                id: kind.id(),
+                kind: *kind,
+                quantity: 0,
+                capacity: STACK_CAPACITY,
+                batches: VecDeque::new(),
+            }).collect(),
+            supplies: vec![0; Supply::ALL.len()],
+            toys: vec![],
+        }
+    }
+
+    /// Adds up to `amount` portions bought on `date`, limited by the stack capacity, and returns how many were stored.
+    pub fn fill_inventory(&mut self, kind: FoodKind, amount: u32, date: NaiveDate) -> u32 {
+        let stack = &mut self.stacks[kind.id()];
+        let added = amount.min(stack.capacity.saturating_sub(stack.quantity));
+        if added == 0 {
+            return 0;
+        }
+        stack.quantity += added;
+        match stack.batches.back_mut() {
+            Some(batch) if batch.purchased == date => batch.quantity += added,
+            _ => stack.batches.push_back(Batch {
+                purchased: date,
+                expires: date + kind.shelf_life(),
+                quantity: added,
+            }),
+        }
+        added
+    }
+
+    /// Takes the oldest portion of `kind`.
+    pub fn take(&mut self, kind: FoodKind, today: NaiveDate) -> Option<Portion> {
+        let stack = &mut self.stacks[kind.id()];
+        let batch = stack.batches.front_mut()?;
+        let portion = Portion { spoiled: batch.is_spoiled(today) };
+        batch.quantity -= 1;
+        if batch.quantity == 0 {
+            stack.batches.pop_front();
+        }
+        stack.quantity -= 1;
+        Some(portion)
+    }
+
+    /// Whether the next portion of `kind` handed out is spoiled.
+    pub fn next_spoiled(&self, kind: FoodKind, today: NaiveDate) -> bool {
+        self.stacks[kind.id()].batches.front().is_some_and(|batch| batch.is_spoiled(today))
+    }
+
+    /// Throws away every spoiled batch and returns how many portions were discarded.
+    pub fn discard_spoiled(&mut self, today: NaiveDate) -> u32 {
+        let mut discarded = 0;
+        for stack in self.stacks.iter_mut() {
+            while let Some(batch) = stack.batches.front() {
+                if !batch.is_spoiled(today) {
+                    break;
+                }
+                discarded += batch.quantity;
+                stack.quantity -= batch.quantity;
+                stack.batches.pop_front();
+            }
+        }
+        discarded
+    }
+
+    pub fn count(&self, kind: FoodKind) -> u32 {
+        self.stacks[kind.id()].quantity
+    }
+
+    pub fn total(&self) -> u32 {
+        self.stacks.iter().map(|stack| stack.quantity).sum()
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    pub fn add_supply(&mut self, supply: Supply, amount: u32) {
+        self.supplies[supply.id()] += amount;
+    }
+
+    pub fn take_supply(&mut self, supply: Supply, amount: u32) -> bool {
+        let count = &mut self.supplies[supply.id()];
+        if *count < amount {
+            return false;
+        }
+        *count -= amount;
+        true
+    }
+
+    pub fn supply_count(&self, supply: Supply) -> u32 {
+        self.supplies[supply.id()]
+    }
+
+    /// What changed from `before` to `after`.
+    pub fn delta(before: &Inventory, after: &Inventory) -> InventoryDelta {
+        let mut batches = vec![];
+        for (old, new) in before.stacks.iter().zip(&after.stacks) {
+            let quantity = |stack: &Stack, purchased: NaiveDate| {
+                stack.batches.iter().find(|batch| batch.purchased == purchased).map_or(0, |batch| batch.quantity as i64)
+            };
+            let mut dates: Vec<NaiveDate> = old.batches.iter().chain(&new.batches).map(|batch| batch.purchased).collect();
+            dates.sort();
+            dates.dedup();
+            for purchased in dates {
+                let delta = quantity(new, purchased) - quantity(old, purchased);
+                if delta != 0 {
+                    batches.push((old.kind, purchased, delta));
+                }
+            }
+        }
+        let toys = |inventory: &Inventory| inventory.toys.iter().map(|toy| (toy.kind, toy.durability)).collect::<Vec<_>>();
+        InventoryDelta {
+            batches,
+            supplies: before.supplies.iter().zip(&after.supplies).map(|(old, new)| *new as i64 - *old as i64).collect(),
+            toys_added: missing(&toys(after), &toys(before)),
+            toys_removed: missing(&toys(before), &toys(after)),
+        }
+    }
+
+    /// Whether `delta` can be applied, or reverted when `forward` is false: what it takes out must
+    /// still be in stock and what it puts back must fit in storage.
+    pub fn can_apply(&self, delta: &InventoryDelta, forward: bool) -> bool {
+        let sign = if forward { 1 } else { -1 };
+        let mut added = vec![0; self.stacks.len()];
+        for (kind, purchased, quantity) in &delta.batches {
+            let stack = &self.stacks[kind.id()];
+            let quantity = quantity * sign;
+            let held = stack.batches.iter().find(|batch| batch.purchased == *purchased).map_or(0, |batch| batch.quantity as i64);
+            if held + quantity < 0 {
+                return false;
+            }
+            added[kind.id()] += quantity;
+        }
+        if self.stacks.iter().zip(added).any(|(stack, added)| stack.quantity as i64 + added > stack.capacity as i64) {
+            return false;
+        }
+        if self.supplies.iter().zip(&delta.supplies).any(|(count, change)| (*count as i64 + change * sign) < 0) {
+            return false;
+        }
+        let removed = if forward { &delta.toys_removed } else { &delta.toys_added };
+        let toys: Vec<(ToyKind, u32)> = self.toys.iter().map(|toy| (toy.kind, toy.durability)).collect();
+        missing(removed, &toys).is_empty()
+    }
+
+    /// Applies `delta`, or reverts it when `forward` is false, without going below zero or above capacity.
+    pub fn apply(&mut self, delta: &InventoryDelta, forward: bool) {
+        let sign = if forward { 1 } else { -1 };
+        for (kind, purchased, quantity) in &delta.batches {
+            let stack = &mut self.stacks[kind.id()];
+            let quantity = (quantity * sign).min(stack.capacity.saturating_sub(stack.quantity) as i64);
+            match stack.batches.iter().position(|batch| batch.purchased == *purchased) {
+                Some(index) => {
+                    let batch = &mut stack.batches[index];
+                    let new = (batch.quantity as i64 + quantity).max(0) as u32;
+                    stack.quantity = stack.quantity + new - batch.quantity;
+                    batch.quantity = new;
+                    if new == 0 {
+                        stack.batches.remove(index);
+                    }
+                }
+                None if quantity > 0 => {
+                    let index = stack.batches.iter().position(|batch| batch.purchased > *purchased).unwrap_or(stack.batches.len());
+                    stack.batches.insert(index, Batch { purchased: *purchased, expires: *purchased + kind.shelf_life(), quantity: quantity as u32 });
+                    stack.quantity += quantity as u32;
+                }
+                None => {}
+            }
+        }
+        for (count, change) in self.supplies.iter_mut().zip(&delta.supplies) {
+            *count = (*count as i64 + change * sign).max(0) as u32;
+        }
+        let (added, removed) = if forward { (&delta.toys_added, &delta.toys_removed) } else { (&delta.toys_removed, &delta.toys_added) };
+        for (kind, durability) in removed {
+            if let Some(index) = self.toys.iter().position(|toy| toy.kind == *kind && toy.durability == *durability) {
+                self.toys.remove(index);
+            }
+        }
+        self.toys.extend(added.iter().map(|(kind, durability)| Toy { kind: *kind, durability: *durability }));
+    }
+
+    pub fn add_toys(&mut self, kind: ToyKind, amount: u32) {
+        self.toys.extend((0..amount).map(|_| Toy::new(kind)));
+    }
+
+    /// Wears the most used toy of `kind` by one session and returns whether it broke, or `None` without such a toy.
+    pub fn wear_toy(&mut self, kind: ToyKind) -> Option<bool> {
+        let index = self
+            .toys
+            .iter()
+            .enumerate()
+            .filter(|(_, toy)| toy.kind == kind)
+            .min_by_key(|(_, toy)| toy.durability)
+            .map(|(index, _)| index)?;
+        self.toys[index].durability -= 1;
+        if self.toys[index].durability == 0 {
+            self.toys.remove(index);
+            return Some(true);
         }
+        Some(false)
+    }
+
+    pub fn toy_count(&self, kind: ToyKind) -> usize {
+        self.toys.iter().filter(|toy| toy.kind == kind).count()
+    }
+
+    pub fn toys(&self) -> &[Toy] {
+        &self.toys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn fill_stops_at_capacity() {
+        let mut inventory = Inventory::get_inventory();
+        assert_eq!(inventory.fill_inventory(FoodKind::DryKibble, STACK_CAPACITY - 10, day(1)), STACK_CAPACITY - 10);
+        assert_eq!(inventory.fill_inventory(FoodKind::DryKibble, 50, day(2)), 10);
+        assert_eq!(inventory.fill_inventory(FoodKind::DryKibble, 1, day(3)), 0);
+        assert_eq!(inventory.count(FoodKind::DryKibble), STACK_CAPACITY);
+        assert_eq!(inventory.count(FoodKind::WetPouch), 0);
+        assert_eq!(inventory.stacks()[FoodKind::DryKibble.id()].batches.len(), 2);
+    }
+
+    #[test]
+    fn take_empties_one_stack() {
+        let mut inventory = Inventory::get_inventory();
+        inventory.fill_inventory(FoodKind::Treat, 2, day(1));
+        assert!(inventory.take(FoodKind::Treat, day(1)).is_some());
+        assert!(inventory.take(FoodKind::Treat, day(1)).is_some());
+        assert!(inventory.take(FoodKind::Treat, day(1)).is_none());
+        assert!(inventory.take(FoodKind::DryKibble, day(1)).is_none());
+        assert_eq!(inventory.total(), 0);
+    }
+
+    #[test]
+    fn take_uses_the_oldest_batch_first() {
+        let mut inventory = Inventory::get_inventory();
+        inventory.fill_inventory(FoodKind::WetPouch, 1, day(1));
+        inventory.fill_inventory(FoodKind::WetPouch, 2, day(5));
+        inventory.fill_inventory(FoodKind::WetPouch, 1, day(5));
+        let stack = &inventory.stacks()[FoodKind::WetPouch.id()];
+        assert_eq!(stack.batches.iter().map(|batch| batch.quantity).collect::<Vec<_>>(), vec![1, 3]);
+
+        inventory.take(FoodKind::WetPouch, day(6));
+        let stack = &inventory.stacks()[FoodKind::WetPouch.id()];
+        assert_eq!(stack.batches.len(), 1);
+        assert_eq!(stack.batches[0].purchased, day(5));
+        assert_eq!(stack.quantity, 3);
     }
 
-    pub fn fill_inventory(&mut self, amount: u32) {
-        for x in 0..amount {
-            self.slot.push(Eat {
-                id: x,
-                name: "CatEat".to_string(),
-                food_value: 2.0,
-            })
+    #[test]
+    fn spoiled_food_is_flagged_and_discarded() {
+        let mut inventory = Inventory::get_inventory();
+        inventory.fill_inventory(FoodKind::WetPouch, 2, day(1));
+        inventory.fill_inventory(FoodKind::WetPouch, 3, day(10));
+        let expired = day(1) + FoodKind::WetPouch.shelf_life();
+        assert!(!inventory.next_spoiled(FoodKind::WetPouch, expired - Duration::days(1)));
+        assert!(inventory.next_spoiled(FoodKind::WetPouch, expired));
+        assert!(inventory.take(FoodKind::WetPouch, expired).unwrap().spoiled);
+
+        assert_eq!(inventory.discard_spoiled(expired), 1);
+        assert_eq!(inventory.count(FoodKind::WetPouch), 3);
+        assert!(!inventory.take(FoodKind::WetPouch, expired).unwrap().spoiled);
+    }
+
+    #[test]
+    fn delta_reverts_and_replays_a_meal() {
+        let mut inventory = Inventory::get_inventory();
+        inventory.fill_inventory(FoodKind::DryKibble, 1, day(1));
+        inventory.fill_inventory(FoodKind::DryKibble, 5, day(2));
+        let before = inventory.clone();
+        inventory.take(FoodKind::DryKibble, day(3));
+        let delta = Inventory::delta(&before, &inventory);
+        assert!(!delta.is_empty());
+        assert!(Inventory::delta(&inventory, &inventory).is_empty());
+
+        assert!(inventory.can_apply(&delta, false));
+        inventory.apply(&delta, false);
+        assert_eq!(inventory.count(FoodKind::DryKibble), 6);
+        assert_eq!(inventory.stacks()[FoodKind::DryKibble.id()].batches[0].purchased, day(1));
+
+        inventory.apply(&delta, true);
+        assert_eq!(inventory.count(FoodKind::DryKibble), 5);
+        assert_eq!(inventory.stacks()[FoodKind::DryKibble.id()].batches.len(), 1);
+    }
+
+    #[test]
+    fn undoing_a_purchase_needs_the_stock_to_be_there() {
+        let mut inventory = Inventory::get_inventory();
+        let before = inventory.clone();
+        inventory.fill_inventory(FoodKind::WetPouch, 100, day(1));
+        let delta = Inventory::delta(&before, &inventory);
+        for _ in 0..50 {
+            inventory.take(FoodKind::WetPouch, day(2));
         }
+        assert!(!inventory.can_apply(&delta, false));
+        assert!(inventory.can_apply(&delta, true));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn replaying_a_purchase_stays_within_capacity() {
+        let mut inventory = Inventory::get_inventory();
+        let before = inventory.clone();
+        inventory.fill_inventory(FoodKind::Treat, 100, day(1));
+        let delta = Inventory::delta(&before, &inventory);
+        inventory.fill_inventory(FoodKind::Treat, STACK_CAPACITY, day(2));
+        assert!(!inventory.can_apply(&delta, true));
+
+        inventory.apply(&delta, true);
+        assert_eq!(inventory.count(FoodKind::Treat), STACK_CAPACITY);
+        assert_eq!(inventory.fill_inventory(FoodKind::Treat, 1, day(3)), 0);
+    }
+}
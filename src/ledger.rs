@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDateTime;
+use crate::calendar::month_key;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Opening,
+    Debug,
+    Food,
+    Supplies,
+    Vet,
+    RunningCosts,
+    Salaries,
+    AdoptionFee,
+    Donation,
+    Sponsorship,
+    Loan,
+    LoanRepayment,
+    Interest,
+    BankFees,
+}
+
+impl Category {
+    pub const ALL: [Category; 14] = [
+        Category::Opening,
+        Category::Debug,
+        Category::Food,
+        Category::Supplies,
+        Category::Vet,
+        Category::RunningCosts,
+        Category::Salaries,
+        Category::AdoptionFee,
+        Category::Donation,
+        Category::Sponsorship,
+        Category::Loan,
+        Category::LoanRepayment,
+        Category::Interest,
+        Category::BankFees,
+    ];
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Category::Opening => write!(f, "Opening balance"),
+            Category::Debug => write!(f, "Debug"),
+            Category::Food => write!(f, "Food"),
+            Category::Supplies => write!(f, "Supplies"),
+            Category::Vet => write!(f, "Vet"),
+            Category::RunningCosts => write!(f, "Running costs"),
+            Category::Salaries => write!(f, "Salaries"),
+            Category::AdoptionFee => write!(f, "Adoption fee"),
+            Category::Donation => write!(f, "Donation"),
+            Category::Sponsorship => write!(f, "Sponsorship"),
+            Category::Loan => write!(f, "Loan"),
+            Category::LoanRepayment => write!(f, "Loan repayment"),
+            Category::Interest => write!(f, "Interest"),
+            Category::BankFees => write!(f, "Bank fees"),
+        }
+    }
+}
+
+/// One credit (positive amount) or debit (negative amount).
+pub struct Entry {
+    pub date: NaiveDateTime,
+    pub category: Category,
+    pub amount: i64,
+    pub label: String,
+    pub cat_id: Option<u64>,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct MonthTotals {
+    pub credit: u64,
+    pub debit: u64,
+}
+
+pub struct Ledger {
+    entries: Vec<Entry>,
+    balance: i64,
+}
+
+impl Ledger {
+    pub fn new(date: NaiveDateTime, opening: u64) -> Self {
+        let mut ledger = Self {
+            entries: Vec::new(),
+            balance: 0,
+        };
+        ledger.record(Entry {
+            date,
+            category: Category::Opening,
+            amount: opening as i64,
+            label: String::new(),
+            cat_id: None,
+        });
+        ledger
+    }
+
+    /// Appends an entry; the balance is only ever changed here so it always equals the sum of the entries.
+    pub fn record(&mut self, entry: Entry) {
+        self.balance += entry.amount;
+        self.entries.push(entry);
+    }
+
+    pub fn balance(&self) -> i64 {
+        self.balance
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn monthly_totals(&self) -> BTreeMap<(i32, u32), MonthTotals> {
+        let mut months = BTreeMap::<(i32, u32), MonthTotals>::new();
+        for entry in &self.entries {
+            let totals = months.entry(month_key(entry.date.date())).or_default();
+            if entry.amount >= 0 {
+                totals.credit += entry.amount as u64;
+            } else {
+                totals.debit += entry.amount.unsigned_abs();
+            }
+        }
+        months
+    }
+
+    pub fn export_csv(&self, path: &Path) -> std::io::Result<()> {
+        let mut csv = String::from("date,category,amount,label,cat_id\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{},\"{}\",{}\n",
+                entry.date.format("%Y-%m-%d %H:%M"),
+                entry.category,
+                entry.amount,
+                entry.label.replace('"', "\"\""),
+                entry.cat_id.map_or(String::new(), |id| id.to_string()),
+            ));
+        }
+        fs::write(path, csv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap().and_hms_opt(8, 0, 0).unwrap()
+    }
+
+    fn entry(date: NaiveDateTime, category: Category, amount: i64) -> Entry {
+        Entry { date, category, amount, label: String::new(), cat_id: None }
+    }
+
+    #[test]
+    fn balance_is_the_sum_of_the_entries() {
+        let mut ledger = Ledger::new(at(1, 1), 1000);
+        ledger.record(entry(at(1, 2), Category::Food, -250));
+        ledger.record(entry(at(1, 3), Category::Donation, 40));
+        ledger.record(entry(at(1, 4), Category::Vet, -900));
+        assert_eq!(ledger.balance(), -110);
+        assert_eq!(ledger.entries().len(), 4);
+        assert_eq!(ledger.balance(), ledger.entries().iter().map(|entry| entry.amount).sum::<i64>());
+    }
+
+    #[test]
+    fn monthly_totals_split_credits_and_debits() {
+        let mut ledger = Ledger::new(at(1, 1), 1000);
+        ledger.record(entry(at(1, 20), Category::Food, -300));
+        ledger.record(entry(at(2, 1), Category::AdoptionFee, 150));
+        ledger.record(entry(at(2, 14), Category::RunningCosts, -20));
+        ledger.record(entry(at(2, 28), Category::Vet, -80));
+
+        let months = ledger.monthly_totals();
+        assert_eq!(months.len(), 2);
+        let january = months[&(2024, 1)];
+        assert_eq!((january.credit, january.debit), (1000, 300));
+        let february = months[&(2024, 2)];
+        assert_eq!((february.credit, february.debit), (150, 100));
+    }
+}
@@ -1,10 +1,28 @@
 use eframe::egui;
 use eframe::egui::{Align2, Button};
 use egui_toast::{Toast, ToastKind, ToastOptions, Toasts};
-use std::time::{Duration, Instant};
+use chrono::{Datelike, Local, NaiveDate};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::cat::CatInfo;
-use crate::inventory::Inventory;
+use crate::adoption::AdoptionCenter;
+use crate::bank::{Bank, LOAN_OFFERS, OVERDRAFT_LIMIT, OVERDRAFT_RATE};
+use crate::calendar::{Calendar, Rollover, Season};
+use crate::cat::{CatInfo, CatState};
+use crate::economy::Income;
+use crate::history::{Change, Command, History};
+use crate::inventory::{FoodKind, Inventory, Supply};
+use crate::ledger::{Category, Entry, Ledger};
+use crate::shop::{Offer, Product, VetService, OFFERS};
+use crate::room::Room;
+use crate::rules::{Action, Comparison, Condition, Rule, Rules, Subject};
+use crate::session::{Event, Fingerprint, Input, Recorder, Session, SESSION_LOG};
+use crate::social::{Relation, Relationships};
+use crate::staff::{Shift, Staff};
+use crate::stats::{Population, ShelterStats};
+use crate::toy::ToyKind;
+use crate::volunteer::{Volunteers, VOLUNTEER_SHIFTS, WEEK};
 
 mod cat;
 mod color;
@@ -12,38 +30,112 @@ mod race;
 mod log_color;
 mod cat_name;
 mod inventory;
+mod chart;
+mod calendar;
+mod stats;
+mod shop;
+mod economy;
+mod ledger;
+mod bank;
+mod adoption;
+mod intake;
+mod room;
+mod personality;
+mod social;
+mod toy;
+mod staff;
+mod volunteer;
+mod rules;
+mod history;
+mod rng;
+mod session;
 
 
 fn main() -> eframe::Result {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(path) = flag(&args, "--replay") {
+        match replay(Path::new(path)) {
+            Ok(report) => {
+                println!("{}", report);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Replay failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let seed = match flag(&args, "--seed") {
+        Some(seed) => seed.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid seed: {}", seed);
+            std::process::exit(2);
+        }),
+        None => SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64),
+    };
+    let start = Local::now().date_naive();
+    let mut app = MyApp::new(seed, start);
+    match Recorder::create(Path::new(SESSION_LOG), seed, start, app.fingerprint()) {
+        Ok(recorder) => app.recorder = Some(recorder),
+        Err(e) => eprintln!("Can't record the session to {}: {}", SESSION_LOG, e),
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1500.0, 1000.0]),
         vsync: true,
         ..Default::default()
     };
 
-    let cats = CatInfo::spawn_new_cat(2);
-    let mut inv = Inventory::get_inventory();
-    inv.fill_inventory(50);
     eframe::run_native(
         "Cat Manager",
         options,
         Box::new(|cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(MyApp {
-                show_confirmation_dialog: false,
-                allowed_to_close: false,
-                columns: 0,
-                cat_vec: cats,
-                last_update: Instant::now(),
-                inventory: inv,
-                money: 1000,
-            }))
+            Ok(Box::new(app))
         }),
     )
 }
 
+/// Value following `name` on the command line.
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|arg| arg == name).and_then(|index| args.get(index + 1)).map(String::as_str)
+}
+
+/// Plays the session logged at `path` again from its seed and inputs, checking after every step
+/// that the simulation reaches the state it was recorded in.
+fn replay(path: &Path) -> Result<String, String> {
+    let session = Session::load(path)?;
+    let mut app = MyApp::new(session.seed, session.start);
+    if app.fingerprint() != session.state {
+        return Err("the starting state differs".to_string());
+    }
+    let mut inputs = 0;
+    for (line, event) in &session.events {
+        let mut toasts = Toasts::new();
+        let (tick, now, state) = match event {
+            Event::Input { tick, now, state, input } => {
+                app.execute(input, &mut toasts);
+                inputs += 1;
+                (*tick, *now, *state)
+            }
+            Event::Tick { tick, now, state } => {
+                app.tick(&mut toasts);
+                (*tick, *now, *state)
+            }
+        };
+        if (app.ticks, app.calendar.now) != (tick, now) {
+            return Err(format!("line {}: expected tick {} at {}, replay is at tick {} at {}", line, tick, now, app.ticks, app.calendar.now));
+        }
+        let actual = app.fingerprint();
+        if actual != state {
+            return Err(format!("line {}: state diverged at {} (expected {:016x}, got {:016x})", line, now, state, actual));
+        }
+    }
+    Ok(format!("Replay of {} matches: {} ticks, {} inputs, final state {:016x} at {}",
+               path.display(), app.ticks, inputs, app.fingerprint(), app.calendar.now.format("%d/%m/%Y %Hh")))
+}
+
 struct MyApp {
     show_confirmation_dialog: bool,
     allowed_to_close: bool,
@@ -51,27 +143,833 @@ struct MyApp {
     cat_vec: Vec<CatInfo>,
     last_update: Instant,
     inventory: Inventory,
-    money: u64,
+    ledger: Ledger,
+    ledger_filter: Option<Category>,
+    detail_windows: Vec<u64>,
+    tab: Tab,
+    calendar: Calendar,
+    stats: ShelterStats,
+    debug_mode: bool,
+    show_shop: bool,
+    vet_cat: Option<u64>,
+    income: Income,
+    bank: Bank,
+    show_bank: bool,
+    game_over: Option<String>,
+    adoption: AdoptionCenter,
+    selected_adopter: Option<u32>,
+    rooms: Vec<Room>,
+    relationships: Relationships,
+    staff: Staff,
+    volunteers: Volunteers,
+    rules: Rules,
+    rule_draft: Rule,
+    history: History,
+    rename_draft: String,
+    seed: u64,
+    /// Simulation ticks run since the start of the session.
+    ticks: u64,
+    next_cat_id: u64,
+    recorder: Option<Recorder>,
+}
+
+#[derive(PartialEq)]
+enum Tab {
+    Cats,
+    Dashboard,
+    Inventory,
+    Ledger,
+    Adoption,
+    Rooms,
+    Staff,
+    Volunteers,
+    Rules,
+    History,
 }
 
 impl MyApp {
-    fn handle_cats_update(&mut self, toasts: &mut Toasts) {
-        if self.last_update.elapsed() >= Duration::from_secs(30) {
-            let mut rm_cat = vec![];
-            for index in 0..self.cat_vec.len() {
-                if let None = self.cat_vec[index].update() {
-                    rm_cat.push(index);
-                    toast(toasts, (format!("{}", self.cat_vec[index]), ToastKind::Error), 20.0);
-                }else {
-                    toast(toasts, (self.cat_vec[index].minimal_info(), ToastKind::Success), 10.0);
+    /// Opens a new shelter; the same seed and start date always give the same shelter.
+    fn new(seed: u64, start: NaiveDate) -> Self {
+        rng::seed(seed);
+        let calendar = Calendar::new(start);
+        let mut inventory = Inventory::get_inventory();
+        inventory.fill_inventory(FoodKind::DryKibble, 30, start);
+        inventory.fill_inventory(FoodKind::WetPouch, 10, start);
+        inventory.fill_inventory(FoodKind::KittenFormula, 10, start);
+        inventory.add_toys(ToyKind::Wand, 1);
+        inventory.add_toys(ToyKind::Ball, 2);
+        inventory.add_supply(Supply::Brush, 1);
+        let mut app = MyApp {
+            show_confirmation_dialog: false,
+            allowed_to_close: false,
+            columns: 0,
+            cat_vec: vec![],
+            last_update: Instant::now(),
+            inventory,
+            ledger: Ledger::new(calendar.now, 1000),
+            detail_windows: vec![],
+            tab: Tab::Cats,
+            calendar,
+            ledger_filter: None,
+            stats: ShelterStats::new(),
+            debug_mode: false,
+            show_shop: false,
+            vet_cat: None,
+            income: Income::new(),
+            bank: Bank::new(),
+            show_bank: false,
+            game_over: None,
+            adoption: AdoptionCenter::new(),
+            selected_adopter: None,
+            rooms: room::default_rooms(),
+            relationships: Relationships::new(),
+            staff: Staff::new(),
+            volunteers: Volunteers::new(),
+            rules: Rules::new(),
+            rule_draft: Rule { id: 0, enabled: true, conditions: vec![], action: Action::Groom },
+            history: History::new(),
+            rename_draft: String::new(),
+            seed,
+            ticks: 0,
+            next_cat_id: 0,
+            recorder: None,
+        };
+        for cat in CatInfo::spawn_new_cat(2, start) {
+            app.admit(cat, false);
+        }
+        app
+    }
+
+    /// Brings a new cat into the shelter under a new id, through quarantine if asked.
+    fn admit(&mut self, mut cat: CatInfo, quarantine: bool) {
+        cat.id = self.next_cat_id;
+        self.next_cat_id += 1;
+        if quarantine {
+            cat.quarantine_until = Some(room::quarantine_end(self.calendar.date()));
+        }
+        self.house(cat);
+    }
+
+    /// Puts a cat in the room best suited to it.
+    fn house(&mut self, mut cat: CatInfo) {
+        cat.room = room::pick_room(&self.rooms, &self.cat_vec, &cat, self.calendar.date());
+        self.cat_vec.push(cat);
+    }
+
+    fn handle_rooms(&mut self, toasts: &mut Toasts, rollover: &Rollover) {
+        for name in room::update_rooms(&self.rooms, &mut self.cat_vec) {
+            toast(toasts, (format!("{} est tombé(e) malade", name), ToastKind::Error), 10.0);
+        }
+
+        if rollover.day {
+            let today = self.calendar.date();
+            let released: Vec<usize> = (0..self.cat_vec.len())
+                .filter(|index| self.cat_vec[*index].quarantine_until.is_some_and(|until| until <= today))
+                .collect();
+            for index in released {
+                self.cat_vec[index].quarantine_until = None;
+                let room = room::pick_room(&self.rooms, &self.cat_vec, &self.cat_vec[index], today);
+                self.cat_vec[index].room = room;
+                toast(toasts, (format!("{} sort de quarantaine ({})", self.cat_vec[index].name, self.rooms[room].name), ToastKind::Info), 10.0);
+            }
+        }
+    }
+
+    fn money(&self) -> i64 {
+        self.ledger.balance()
+    }
+
+    fn credit(&mut self, amount: u64, category: Category, label: String, cat_id: Option<u64>) {
+        self.ledger.record(Entry { date: self.calendar.now, category, amount: amount as i64, label, cat_id });
+    }
+
+    /// Debits `amount` whatever the balance, for bills that can't be refused.
+    fn charge(&mut self, amount: u64, category: Category, label: String, cat_id: Option<u64>) {
+        self.ledger.record(Entry { date: self.calendar.now, category, amount: -(amount as i64), label, cat_id });
+    }
+
+    fn can_afford(&self, amount: u64) -> bool {
+        self.money() - amount as i64 >= -OVERDRAFT_LIMIT
+    }
+
+    /// Spends `amount` if it stays within the overdraft limit.
+    fn spend(&mut self, amount: u64, category: Category, label: String, cat_id: Option<u64>) -> bool {
+        if !self.can_afford(amount) {
+            return false;
+        }
+        self.charge(amount, category, label, cat_id);
+        true
+    }
+
+    /// Runs a player action and records everything it changed so that it can be undone.
+    fn record<R>(&mut self, label: String, action: impl FnOnce(&mut Self) -> R) -> R {
+        let cats: Vec<(u64, CatState)> = self.cat_vec.iter().map(|cat| (cat.id, cat.state())).collect();
+        let inventory = self.inventory.clone();
+        let entries = self.ledger.entries().len();
+        let litter: Vec<f32> = self.rooms.iter().map(|room| room.litter).collect();
+        let date = self.calendar.date();
+        let month = *self.stats.month(date);
+        let loans = self.bank.loans.clone();
+
+        let result = action(self);
+
+        let mut changes = vec![];
+        for cat in &self.cat_vec {
+            match cats.iter().find(|(id, _)| *id == cat.id) {
+                Some((id, before)) => {
+                    let after = cat.state();
+                    if after != *before {
+                        changes.push(Change::Cat { id: *id, before: before.clone(), after });
+                    }
+                }
+                None => changes.push(Change::Admitted(Box::new(cat.clone()))),
+            }
+        }
+        let delta = Inventory::delta(&inventory, &self.inventory);
+        if !delta.is_empty() {
+            changes.push(Change::Inventory(delta));
+        }
+        for entry in &self.ledger.entries()[entries..] {
+            changes.push(Change::Money { amount: entry.amount, category: entry.category, label: entry.label.clone(), cat_id: entry.cat_id });
+        }
+        for (room, before) in self.rooms.iter().zip(litter) {
+            if room.litter != before {
+                changes.push(Change::Litter { room: room.id, amount: room.litter - before });
+            }
+        }
+        let after = *self.stats.month(date);
+        if after.food_used != month.food_used || after.births != month.births {
+            changes.push(Change::Stats { date, food_used: after.food_used - month.food_used, births: after.births - month.births });
+        }
+        let mut ids: Vec<u32> = loans.iter().chain(&self.bank.loans).map(|loan| loan.id).collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            let before = loans.iter().find(|loan| loan.id == id).cloned();
+            let after = self.bank.loan(id).cloned();
+            if before != after {
+                changes.push(Change::Loan { id, before, after });
+            }
+        }
+        self.history.record(Command { label, date: self.calendar.now, changes });
+        result
+    }
+
+    /// Why a change of a recorded action can't be applied again, or reversed when `forward` is false.
+    fn check(&self, change: &Change, forward: bool) -> Result<(), String> {
+        let present = |id: u64| self.cat_vec.iter().any(|cat| cat.id == id);
+        match change {
+            Change::Cat { id, after, .. } if !present(*id) => Err(format!("{} is no longer at the shelter", after.name)),
+            Change::Admitted(cat) if !forward && !present(cat.id) => Err(format!("{} is no longer at the shelter", cat.name)),
+            Change::Inventory(delta) if !self.inventory.can_apply(delta, forward) => Err("the stock has changed since".to_string()),
+            Change::Loan { id, before, after } => {
+                let expected = if forward { before } else { after };
+                if self.bank.loan(*id) != expected.as_ref() {
+                    return Err(format!("loan #{} has changed since", id));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies a change of a recorded action again, or reverses it when `forward` is false.
+    fn apply(&mut self, change: &Change, forward: bool) {
+        match change {
+            Change::Cat { id, before, after } => {
+                if let Some(cat) = self.cat_vec.iter_mut().find(|cat| cat.id == *id) {
+                    if forward {
+                        cat.apply_change(before, after);
+                    } else {
+                        cat.apply_change(after, before);
+                    }
+                }
+            }
+            Change::Inventory(delta) => self.inventory.apply(delta, forward),
+            Change::Admitted(cat) => {
+                let index = self.cat_vec.iter().position(|other| other.id == cat.id);
+                match (forward, index) {
+                    (true, None) => self.cat_vec.push((**cat).clone()),
+                    (false, Some(index)) => {
+                        let cat = self.cat_vec.remove(index);
+                        self.income.cat_left(cat.id);
+                        self.relationships.cat_left(cat.id);
+                        self.detail_windows.retain(|id| *id != cat.id);
+                    }
+                    _ => {}
+                }
+            }
+            Change::Money { amount, category, label, cat_id } => {
+                let (amount, label) = if forward { (*amount, format!("Redo: {}", label)) } else { (-amount, format!("Undo: {}", label)) };
+                self.ledger.record(Entry { date: self.calendar.now, category: *category, amount, label, cat_id: *cat_id });
+            }
+            Change::Litter { room, amount } => {
+                let room = &mut self.rooms[*room];
+                room.litter = (room.litter + if forward { *amount } else { -amount }).clamp(0.0, 100.0);
+            }
+            Change::Stats { date, food_used, births } => {
+                if forward {
+                    self.stats.record_food_used(*date, *food_used);
+                    self.stats.month(*date).births += births;
+                } else {
+                    self.stats.cancel_food_used(*date, *food_used);
+                    let month = self.stats.month(*date);
+                    month.births = month.births.saturating_sub(*births);
+                }
+            }
+            Change::Loan { id, before, after } => self.bank.restore(*id, if forward { after.clone() } else { before.clone() }),
+        }
+    }
+
+    fn undo(&mut self, toasts: &mut Toasts) {
+        let Some(command) = self.history.done.pop_back() else {
+            return;
+        };
+        if let Some(reason) = command.changes.iter().find_map(|change| self.check(change, false).err()) {
+            // Older actions can only be undone after this one, so they are lost with it.
+            self.history.done.clear();
+            toast(toasts, (format!("Can't undo {}: {}", command.label, reason), ToastKind::Warning), 10.0);
+            return;
+        }
+        for change in command.changes.iter().rev() {
+            self.apply(change, false);
+        }
+        toast(toasts, (format!("Undone: {}", command.label), ToastKind::Info), 5.0);
+        self.history.undone.push(command);
+    }
+
+    fn redo(&mut self, toasts: &mut Toasts) {
+        let Some(command) = self.history.undone.pop() else {
+            return;
+        };
+        if let Some(reason) = command.changes.iter().find_map(|change| self.check(change, true).err()) {
+            self.history.undone.clear();
+            toast(toasts, (format!("Can't redo {}: {}", command.label, reason), ToastKind::Warning), 10.0);
+            return;
+        }
+        for change in &command.changes {
+            self.apply(change, true);
+        }
+        toast(toasts, (format!("Redone: {}", command.label), ToastKind::Info), 5.0);
+        self.history.done.push_back(command);
+    }
+
+    /// Buys one pack of `offer` if affordable and returns how many units did not fit in storage.
+    fn buy(&mut self, offer: &Offer) -> Option<u32> {
+        if !self.spend(offer.price, offer.product.category(), format!("{} x{}", offer.product, offer.quantity), None) {
+            return None;
+        }
+        Some(match offer.product {
+            Product::Food(kind) => offer.quantity - self.inventory.fill_inventory(kind, offer.quantity, self.calendar.date()),
+            Product::Supply(supply) => {
+                self.inventory.add_supply(supply, offer.quantity);
+                0
+            }
+            Product::Toy(kind) => {
+                self.inventory.add_toys(kind, offer.quantity);
+                0
+            }
+        })
+    }
+
+    /// Feeds the cat at `index`, recording the food used and soiling its room.
+    fn feed(&mut self, index: usize, kind: FoodKind) -> (String, ToastKind) {
+        let stock = self.inventory.total();
+        let result = self.cat_vec[index].feed(kind, &mut self.inventory, self.calendar.date());
+        let eaten = stock - self.inventory.total();
+        self.stats.record_food_used(self.calendar.date(), eaten);
+        self.rooms[self.cat_vec[index].room].soil(room::LITTER_PER_MEAL * eaten as f32);
+        result
+    }
+
+    fn pay_running_costs(&mut self, toasts: &mut Toasts) {
+        for (label, amount) in economy::daily_costs(self.cat_vec.len(), self.calendar.season()) {
+            if amount == 0 {
+                continue;
+            }
+            self.charge(amount, Category::RunningCosts, label.to_string(), None);
+        }
+        if self.money() < -OVERDRAFT_LIMIT {
+            toast(toasts, (format!("Overdraft limit exceeded: {}", self.money()), ToastKind::Error), 20.0);
+        }
+    }
+
+    fn handle_bank(&mut self, toasts: &mut Toasts) {
+        let bills: Vec<(u32, u64, u64)> = self.bank.loans.iter().map(|loan| (loan.id, loan.interest(), loan.installment())).collect();
+        for (loan_id, interest, installment) in bills {
+            self.charge(interest, Category::Interest, format!("Loan #{}", loan_id), None);
+            let repaid = self.bank.repay(loan_id, installment);
+            self.charge(repaid, Category::LoanRepayment, format!("Loan #{}", loan_id), None);
+        }
+        if self.money() < 0 {
+            let fee = (self.money().unsigned_abs() as f64 * OVERDRAFT_RATE).ceil() as u64;
+            self.charge(fee, Category::BankFees, "Overdraft".to_string(), None);
+        }
+
+        if self.bank.close_month(self.money()) {
+            self.game_over = Some(format!(
+                "The shelter could not pay its debts for {} months.\nAn administrator has taken over.\n\nBalance: {}\nDebt: {}\nCats: {}",
+                self.bank.months_in_default, self.money(), self.bank.debt(), self.cat_vec.len()));
+        } else if self.bank.months_in_default > 0 {
+            toast(toasts, (format!("Month closed past the overdraft limit ({} / {})", self.bank.months_in_default, bank::MONTHS_BEFORE_TAKEOVER), ToastKind::Error), 20.0);
+        }
+    }
+
+    /// Hands a cat, and the cats it is bonded with, over to a waiting adopter and credits the adoption fees.
+    fn adopt(&mut self, adopter_id: u32, cat_id: u64, toasts: &mut Toasts) {
+        if !self.cat_vec.iter().any(|cat| cat.id == cat_id) {
+            return;
+        }
+        let Some(adopter) = self.adoption.take_adopter(adopter_id) else {
+            toast(toasts, ("This adopter is gone".to_string(), ToastKind::Warning), 10.0);
+            return;
+        };
+
+        let partners: Vec<u64> = self.relationships.bonded_with(cat_id);
+        for id in std::iter::once(cat_id).chain(partners) {
+            let Some(index) = self.cat_vec.iter().position(|cat| cat.id == id) else {
+                continue;
+            };
+            let cat = self.cat_vec.remove(index);
+            let (name, fee) = (cat.name.clone(), economy::adoption_fee(&cat));
+            let score = self.adoption.place(adopter.clone(), cat, self.calendar.date());
+            self.credit(fee, Category::AdoptionFee, name.to_string(), Some(id));
+            self.income.cat_left(id);
+            self.relationships.cat_left(id);
+            self.income.adjust_reputation(2.0);
+            self.stats.month(self.calendar.date()).adoptions += 1;
+            toast(toasts, (format!("{} a été adopté(e) par {} ! Compatibilité: {}%, frais d'adoption: {}", name, adopter.name, score, fee), ToastKind::Success), 20.0);
+        }
+    }
+
+    fn handle_adopters(&mut self, toasts: &mut Toasts) {
+        let today = self.calendar.date();
+        if let Some(adopter) = self.adoption.arrive(today, self.income.reputation) {
+            toast(toasts, (format!("{} souhaite adopter un chat", adopter.name), ToastKind::Info), 10.0);
+        }
+        for name in self.adoption.expire(today) {
+            toast(toasts, (format!("{} est reparti(e) sans chat", name), ToastKind::Warning), 10.0);
+        }
+        for mut placement in self.adoption.returns(today) {
+            placement.cat.arrived_date = today;
+            toast(toasts, (format!("{} a ramené {} (compatibilité: {}%)", placement.adopter, placement.cat.name, placement.score), ToastKind::Warning), 20.0);
+            self.house(placement.cat);
+            self.income.adjust_reputation(-3.0);
+        }
+    }
+
+    fn handle_intake(&mut self, toasts: &mut Toasts) {
+        let Some(intake) = intake::roll(self.calendar.date()) else {
+            return;
+        };
+        let names: Vec<&str> = intake.cats.iter().map(|cat| cat.name.as_str()).collect();
+        toast(toasts, (format!("{}: {}", intake.kind, names.join(", ")), ToastKind::Info), 15.0);
+        self.stats.month(self.calendar.date()).intakes += intake.cats.len() as u32;
+        for cat in intake.cats {
+            self.admit(cat, true);
+        }
+    }
+
+    /// Pays the staff every day and lets everyone on shift work through their rooms.
+    fn handle_staff(&mut self, rollover: &Rollover) {
+        if rollover.day {
+            let salaries: Vec<(u64, String)> = self.staff.members.iter().map(|member| (member.daily_salary(), member.to_string())).collect();
+            for (salary, name) in salaries {
+                self.charge(salary, Category::Salaries, name, None);
+            }
+        }
+
+        let today = self.calendar.date();
+        let mut done = vec![];
+        for member in self.staff.members.iter().filter(|member| member.shift.covers(self.calendar.now)) {
+            let work = staff::work(member, &mut self.cat_vec, &mut self.rooms, &mut self.inventory, today);
+            self.stats.record_food_used(today, work.food_used);
+            done.extend(work.log.into_iter().map(|text| format!("{}: {}", member.name, text)));
+        }
+        for text in done {
+            self.staff.record(self.calendar.now, text);
+        }
+    }
+
+    /// Volunteer sessions are logged with the staff actions.
+    fn handle_volunteers(&mut self) {
+        for text in self.volunteers.update(self.calendar.now, &mut self.cat_vec, &mut self.inventory) {
+            self.staff.record(self.calendar.now, text);
+        }
+    }
+
+    /// Runs every automation rule whose conditions hold and logs what it did.
+    fn handle_rules(&mut self) {
+        for (index, cat) in self.rules.due(&self.cat_vec, &self.inventory) {
+            let rule = self.rules.rules[index].id;
+            let text = match (self.rules.rules[index].action, cat) {
+                (Action::Feed(kind), Some(cat)) => self.feed(cat, kind).0,
+                (Action::Play(kind), Some(cat)) => self.cat_vec[cat].play(kind, &mut self.inventory).0,
+                (Action::Groom, Some(cat)) => self.cat_vec[cat].groom(&mut self.inventory).0,
+                (Action::Buy(kind, quantity), _) => {
+                    let Some(offer) = OFFERS.iter().find(|offer| matches!(offer.product, Product::Food(food) if food == kind)) else {
+                        continue;
+                    };
+                    let packs = quantity.div_ceil(offer.quantity);
+                    let bought = (0..packs).take_while(|_| self.buy(offer).is_some()).count() as u32;
+                    if bought < packs {
+                        format!("Bought {} of {} {}, not enough money", bought * offer.quantity, quantity, kind)
+                    } else {
+                        format!("Bought {} {}", bought * offer.quantity, kind)
+                    }
                 }
+                _ => continue,
+            };
+            self.rules.record(self.calendar.now, format!("Rule #{}: {}", rule, text));
+        }
+    }
+
+    fn handle_income(&mut self, toasts: &mut Toasts, rollover: &Rollover) {
+        if let Some(amount) = self.income.donation() {
+            self.credit(amount, Category::Donation, String::new(), None);
+            toast(toasts, (format!("Don reçu: {}", amount), ToastKind::Success), 10.0);
+        }
+
+        if rollover.day {
+            if let Some(sponsorship) = self.income.find_sponsor(&self.cat_vec) {
+                let message = format!("{} parraine maintenant le chat #{} ({} / mois)", sponsorship.sponsor, sponsorship.cat_id, sponsorship.monthly);
+                toast(toasts, (message, ToastKind::Success), 10.0);
+            }
+        }
+
+        if rollover.month {
+            let payments: Vec<(u64, String, u64)> = self.income.sponsorships.iter()
+                .map(|sponsorship| (sponsorship.monthly, sponsorship.sponsor.to_string(), sponsorship.cat_id))
+                .collect();
+            let total: u64 = payments.iter().map(|(amount, _, _)| amount).sum();
+            for (amount, sponsor, cat_id) in payments {
+                self.credit(amount, Category::Sponsorship, sponsor, Some(cat_id));
+            }
+            if total > 0 {
+                toast(toasts, (format!("Parrainages du mois: {}", total), ToastKind::Success), 10.0);
             }
+        }
+    }
+
+    fn handle_cats_update(&mut self, toasts: &mut Toasts) {
+        if self.game_over.is_none() && self.last_update.elapsed() >= Duration::from_secs(30) {
+            self.tick(toasts);
             self.last_update = Instant::now();
+            let (tick, now, state) = (self.ticks, self.calendar.now, self.fingerprint());
+            self.write_session(toasts, |recorder| recorder.tick(tick, now, state));
+        }
+    }
+
+    fn cat_index(&self, id: u64) -> Option<usize> {
+        self.cat_vec.iter().position(|cat| cat.id == id)
+    }
+
+    /// Hash of the simulated state, compared after every step of a replay.
+    fn fingerprint(&self) -> u64 {
+        // Dates go in as numbers: chrono's own hashes depend on its internal layout.
+        let mut hasher = Fingerprint::new();
+        (self.calendar.now.and_utc().timestamp(), self.ticks, self.money(), self.ledger.entries().len(), self.game_over.is_some()).hash(&mut hasher);
+        for cat in &self.cat_vec {
+            let quarantine = cat.quarantine_until.map(|until| until.num_days_from_ce());
+            (cat.id, &cat.name, cat.age, cat.room, cat.sick, cat.sleep, quarantine).hash(&mut hasher);
+            for value in [cat.weight, cat.health, cat.food, cat.mood, cat.energy, cat.stress, cat.coat] {
+                value.to_bits().hash(&mut hasher);
+            }
+        }
+        for stack in self.inventory.stacks() {
+            for batch in &stack.batches {
+                (stack.id, batch.quantity, batch.purchased.num_days_from_ce()).hash(&mut hasher);
+            }
+        }
+        for supply in Supply::ALL {
+            self.inventory.supply_count(supply).hash(&mut hasher);
+        }
+        for toy in self.inventory.toys() {
+            (toy.kind as u8, toy.durability).hash(&mut hasher);
+        }
+        for room in &self.rooms {
+            room.litter.to_bits().hash(&mut hasher);
+        }
+        for member in &self.staff.members {
+            (member.id, member.shift as u8, member.room).hash(&mut hasher);
+        }
+        for volunteer in &self.volunteers.roster {
+            (volunteer.id, volunteer.sessions, volunteer.no_shows).hash(&mut hasher);
+        }
+        for rule in &self.rules.rules {
+            (rule.id, rule.enabled).hash(&mut hasher);
+        }
+        for adopter in &self.adoption.adopters {
+            adopter.id.hash(&mut hasher);
+        }
+        (self.adoption.placements.len(), self.bank.debt(), self.income.sponsorships.len(), self.income.reputation.to_bits()).hash(&mut hasher);
+        (self.history.done.len(), self.history.undone.len()).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Writes to the session log, and stops recording when the log can't be written to.
+    fn write_session(&mut self, toasts: &mut Toasts, write: impl FnOnce(&mut Recorder) -> std::io::Result<()>) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        if let Err(e) = write(recorder) {
+            toast(toasts, (format!("Session recording stopped: {}", e), ToastKind::Error), 20.0);
+            self.recorder = None;
+        }
+    }
+
+    /// Carries out a player action and appends it to the session log, unless the shelter was taken over.
+    fn perform(&mut self, input: Input, toasts: &mut Toasts) {
+        if self.game_over.is_some() {
+            return;
+        }
+        self.execute(&input, toasts);
+        let (tick, now, state) = (self.ticks, self.calendar.now, self.fingerprint());
+        self.write_session(toasts, |recorder| recorder.input(tick, now, state, &input));
+    }
 
-            for x in rm_cat.iter().rev() {
-                self.cat_vec.remove(*x);
+    /// Carries out a player action, recording what it changes so that it can be undone.
+    fn execute(&mut self, input: &Input, toasts: &mut Toasts) {
+        match input {
+            Input::Feed(id, kind) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Feed {} with {}", self.cat_vec[cat].name, kind);
+                let message = self.record(label, |app| app.feed(cat, *kind));
+                toast(toasts, message, 10.0);
             }
+            Input::Play(id, kind) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Play with {} ({})", self.cat_vec[cat].name, kind);
+                let message = self.record(label, |app| app.cat_vec[cat].play(*kind, &mut app.inventory));
+                toast(toasts, message, 10.0);
+            }
+            Input::Groom(id) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Groom {}", self.cat_vec[cat].name);
+                let message = self.record(label, |app| app.cat_vec[cat].groom(&mut app.inventory));
+                toast(toasts, message, 10.0);
+            }
+            Input::Pet(id) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Pet {}", self.cat_vec[cat].name);
+                let message = self.record(label, |app| app.cat_vec[cat].pet());
+                toast(toasts, message, 10.0);
+            }
+            Input::Rest(id) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Let {} rest", self.cat_vec[cat].name);
+                let message = self.record(label, |app| app.cat_vec[cat].let_rest());
+                toast(toasts, message, 10.0);
+            }
+            Input::Rename(id, name) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("Rename {} to {}", self.cat_vec[cat].name, name);
+                self.record(label, |app| app.cat_vec[cat].name = name.clone());
+            }
+            Input::Mate(id, mate_id) => {
+                let (Some(cat), Some(mate)) = (self.cat_index(*id), self.cat_index(*mate_id)) else {
+                    return;
+                };
+                match self.cat_vec[cat].mate(&self.cat_vec[mate], self.calendar.date()) {
+                    Ok(kitten) => {
+                        let label = format!("Mate {} with {}", self.cat_vec[cat].name, self.cat_vec[mate].name);
+                        self.record(label, |app| {
+                            app.admit(kitten, false);
+                            app.stats.month(app.calendar.date()).births += 1;
+                        });
+                    }
+                    Err(e) => toast(toasts, (e, ToastKind::Warning), 10.0),
+                }
+            }
+            Input::SpawnCat => self.record("Spawn new cat".to_string(), |app| app.admit(CatInfo::new_cat(app.calendar.date()), false)),
+            Input::AgeCats => self.record("Cat age 50+".to_string(), |app| {
+                for x in 0..app.cat_vec.len() {
+                    app.cat_vec[x].age = app.cat_vec[x].age.saturating_add(50);
+                }
+            }),
+            Input::AddMoney => self.record("Add 1000 Money".to_string(), |app| app.credit(1000, Category::Debug, String::new(), None)),
+            Input::AddFood(kind) => {
+                let added = self.record(format!("Add 1000 {}", kind), |app| app.inventory.fill_inventory(*kind, 1000, app.calendar.date()));
+                if added < 1000 {
+                    toast(toasts, (format!("{} storage full, only {} added", kind, added), ToastKind::Warning), 10.0);
+                }
+            }
+            Input::Buy(offer) => {
+                let Some(offer) = OFFERS.get(*offer) else {
+                    return;
+                };
+                let label = format!("Buy {} x{}", offer.product, offer.quantity);
+                if let Some(lost) = self.record(label, |app| app.buy(offer)).filter(|lost| *lost > 0) {
+                    toast(toasts, (format!("{} storage full, {} portions lost", offer.product, lost), ToastKind::Warning), 10.0);
+                }
+            }
+            Input::Vet(service, id) => {
+                let Some(cat) = self.cat_index(*id) else {
+                    return;
+                };
+                let label = format!("{} for {} #{}", service, self.cat_vec[cat].name, id);
+                let message = self.record(label, |app| {
+                    if !app.spend(service.price(), Category::Vet, service.to_string(), Some(*id)) {
+                        return None;
+                    }
+                    Some(service.apply(&mut app.cat_vec[cat]))
+                });
+                if let Some(message) = message {
+                    toast(toasts, (message, ToastKind::Success), 10.0);
+                }
+            }
+            Input::Clean(room) => {
+                let Some(name) = self.rooms.get(*room).map(|room| room.name) else {
+                    return;
+                };
+                self.record(format!("Clean {}", name), |app| {
+                    if app.inventory.take_supply(Supply::Litter, 1) {
+                        app.rooms[*room].clean();
+                    }
+                });
+            }
+            Input::Move(id, room) => {
+                let (Some(cat), Some(target)) = (self.cat_index(*id), self.rooms.get(*room)) else {
+                    return;
+                };
+                match target.accepts(&self.cat_vec[cat], self.calendar.date()) {
+                    Ok(()) => {
+                        let label = format!("Move {} to {}", self.cat_vec[cat].name, target.name);
+                        self.record(label, |app| app.cat_vec[cat].room = *room);
+                    }
+                    Err(e) => toast(toasts, (e, ToastKind::Warning), 10.0),
+                }
+            }
+            Input::Borrow(offer) => {
+                let Some((amount, rate)) = LOAN_OFFERS.get(*offer).copied() else {
+                    return;
+                };
+                if self.bank.can_borrow(amount) {
+                    self.record(format!("Borrow {}", amount), |app| {
+                        let loan_id = app.bank.borrow(amount, rate, app.calendar.date()).id;
+                        app.credit(amount, Category::Loan, format!("Loan #{}", loan_id), None);
+                    });
+                }
+            }
+            Input::Repay(loan_id) => {
+                let Some(amount) = self.bank.loan(*loan_id).map(|loan| loan.remaining) else {
+                    return;
+                };
+                self.record(format!("Repay loan #{}", loan_id), |app| {
+                    if app.spend(amount, Category::LoanRepayment, format!("Loan #{}", loan_id), None) {
+                        app.bank.repay(*loan_id, amount);
+                    }
+                });
+            }
+            Input::Adopt(adopter_id, cat_id) => self.adopt(*adopter_id, *cat_id, toasts),
+            Input::Hire(id) => {
+                if let Some(member) = self.staff.hire(*id) {
+                    toast(toasts, (format!("{} joins the team as {}", member.name, member.role), ToastKind::Success), 10.0);
+                }
+            }
+            Input::Fire(id) => {
+                if let Some(member) = self.staff.fire(*id) {
+                    toast(toasts, (format!("{} has left the shelter", member.name), ToastKind::Info), 10.0);
+                }
+            }
+            Input::SetShift(id, shift) => {
+                if let Some(member) = self.staff.members.iter_mut().find(|member| member.id == *id) {
+                    member.shift = *shift;
+                }
+            }
+            Input::Assign(id, room) => {
+                if room.is_some_and(|room| self.rooms.get(room).is_none()) {
+                    return;
+                }
+                if let Some(member) = self.staff.members.iter_mut().find(|member| member.id == *id) {
+                    member.room = *room;
+                }
+            }
+            Input::Recruit => {
+                if let Some(volunteer) = self.volunteers.recruit() {
+                    toast(toasts, (format!("{} joins the volunteers", volunteer.name), ToastKind::Success), 10.0);
+                }
+            }
+            Input::Dismiss(id) => self.volunteers.remove(*id),
+            Input::AddRule(rule) => self.rules.add(rule.clone()),
+            Input::ToggleRule(id) => {
+                if let Some(rule) = self.rules.rules.iter_mut().find(|rule| rule.id == *id) {
+                    rule.enabled = !rule.enabled;
+                }
+            }
+            Input::RemoveRule(id) => self.rules.remove(*id),
+            Input::DiscardSpoiled => {
+                let discarded = self.inventory.discard_spoiled(self.calendar.date());
+                toast(toasts, (format!("{} spoiled portions discarded", discarded), ToastKind::Info), 10.0);
+            }
+            Input::Undo => self.undo(toasts),
+            Input::Redo => self.redo(toasts),
+        }
+    }
+
+    /// Runs one simulation tick.
+    fn tick(&mut self, toasts: &mut Toasts) {
+        self.ticks += 1;
+        let rollover = self.calendar.advance();
+        if rollover.day {
+            self.pay_running_costs(toasts);
+        }
+        if rollover.season {
+            let season = self.calendar.season();
+            let note = match season {
+                Season::Spring => ": kitten season, expect many more intakes",
+                Season::Winter => ": heating costs go up",
+                _ => "",
+            };
+            toast(toasts, (format!("{}{}", season, note), ToastKind::Info), 20.0);
+        }
+        self.handle_staff(&rollover);
+        self.handle_volunteers();
+        self.handle_rules();
+        let mut rm_cat = vec![];
+        for index in 0..self.cat_vec.len() {
+            let litter = self.rooms[self.cat_vec[index].room].litter;
+            if self.cat_vec[index].update(self.calendar.now, litter).is_none() {
+                rm_cat.push(index);
+                toast(toasts, (format!("{}", self.cat_vec[index]), ToastKind::Error), 20.0);
+            }else {
+                toast(toasts, (self.cat_vec[index].minimal_info(), ToastKind::Success), 10.0);
+            }
+        }
+
+        for x in rm_cat.iter().rev() {
+            let cat = self.cat_vec.remove(*x);
+            self.income.cat_left(cat.id);
+            self.relationships.cat_left(cat.id);
+        }
+        self.stats.month(self.calendar.date()).deaths += rm_cat.len() as u32;
+        self.handle_rooms(toasts, &rollover);
+        for event in self.relationships.update(&mut self.cat_vec) {
+            toast(toasts, (event, ToastKind::Info), 10.0);
+        }
+
+        if rollover.day {
+            self.handle_adopters(toasts);
+            self.handle_intake(toasts);
+        }
+        self.handle_income(toasts, &rollover);
+        self.income.update_reputation(&self.cat_vec, rm_cat.len());
+        if rollover.month {
+            self.handle_bank(toasts);
         }
+        self.stats.sample(self.calendar.now, self.money(), self.inventory.total());
     }
 }
 
@@ -80,75 +978,189 @@ impl eframe::App for MyApp {
         let mut toasts = Toasts::new().anchor(Align2::RIGHT_TOP, (-10.0, 10.0)).direction(egui::Direction::TopDown);
         self.handle_cats_update(&mut toasts);
 
+        if self.game_over.is_none() && !ctx.wants_keyboard_input() {
+            let (undo, redo) = ctx.input(|input| {
+                let pressed = input.modifiers.command && input.key_pressed(egui::Key::Z);
+                (pressed && !input.modifiers.shift, pressed && input.modifiers.shift)
+            });
+            if undo {
+                self.perform(Input::Undo, &mut toasts);
+            } else if redo {
+                self.perform(Input::Redo, &mut toasts);
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            // The administrator runs the shelter now: nothing can be done anymore.
+            if self.game_over.is_some() {
+                ui.disable();
+            }
+
+            if ui.add(Button::new("Shop")).clicked() {
+                self.show_shop = true;
+            }
+            if ui.add(Button::new("Bank")).clicked() {
+                self.show_bank = true;
+            }
+            ui.checkbox(&mut self.debug_mode, "Debug mode");
+            if self.debug_mode {
+                if ui.add(Button::new("Spawn new cat")).clicked() {
+                    self.perform(Input::SpawnCat, &mut toasts);
+                }
+                if ui.add(Button::new("Cat age 50+")).clicked() {
+                    self.perform(Input::AgeCats, &mut toasts);
+                }
+                if ui.add(Button::new("Add 1000 Money")).clicked(){
+                    self.perform(Input::AddMoney, &mut toasts);
+                }
+                ui.menu_button("Add 1000 Food", |ui| {
+                    for kind in FoodKind::ALL {
+                        if ui.button(kind.to_string()).clicked() {
+                            self.perform(Input::AddFood(kind), &mut toasts);
+                            ui.close_menu();
+                        }
+                    }
+                });
+            }
 
-            if ui.add(Button::new("Spawn new cat")).clicked() {
-                self.cat_vec.push(CatInfo::new_cat());
-            }
-            if ui.add(Button::new("Cat age 50+")).clicked() {
-                for x in 0..self.cat_vec.len() {
-                    self.cat_vec[x].age += 50;
-                }
-            }
-            if ui.add(Button::new("Add 1000 Money")).clicked(){
-                self.money += 1000;
-            }
-            if ui.add(Button::new("Add 1000 Food")).clicked(){
-                self.inventory.fill_inventory(1000);
-            }
-
-            ui.add(egui::Label::new(format!("Food: {}\nMoney: {}", self.inventory.slot.len(), self.money)));
-
-            self.columns = 0;
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.columns(8, |columns| {
-                    for cat in 0..self.cat_vec.len() {
-                        columns[self.columns].group(|ui| {
-                            let image = ui.add(egui::Image::new(format!("file://{}", self.cat_vec[cat].cat_image_byte.clone())).rounding(10.0));
-                            image.context_menu(|ui| {
-                                if ui.add(Button::new("Feed")).clicked() {
-                                    toast(&mut toasts, self.cat_vec[cat].feed(0.1, 5.0, &mut self.inventory.slot), 10.0);
-                                    ui.close_menu();
-                                }
-                                if ui.add(Button::new("Play")).clicked() {
-                                    toast(&mut toasts, self.cat_vec[cat].play(0.05, 2.0), 10.0);
-                                    ui.close_menu();
-                                }
-                                if ui.add(Button::new(format!("Sleep ({})", bool_state!("YES", "NO", self.cat_vec[cat].sleep)))).clicked() {
-                                    toast(&mut toasts, self.cat_vec[cat].toggle_sleep(10.0), 10.0);
-                                    ui.close_menu();
-                                }
-
-                                ui.menu_button("Mate with", |ui| {
-                                    for mate_cat in 0..self.cat_vec.len() {
-                                        if self.cat_vec[cat].gender.ne(&self.cat_vec[mate_cat].gender) && ui.button(self.cat_vec[mate_cat].name).clicked() {
-                                            match self.cat_vec[cat].mate(&self.cat_vec[mate_cat]) {
-                                                Ok(kitten) => {
-                                                    self.cat_vec.push(kitten);
+            ui.horizontal(|ui| {
+                ui.add(egui::Label::new(format!("Food: {}\nMoney: {}\nDebt: {}\nReputation: {:.0}\nDate: {}", self.inventory.total(), self.money(), self.bank.debt(), self.income.reputation, self.calendar.now.format("%d/%m/%Y %Hh"))));
+                chart::clock(ui, self.calendar.now);
+                ui.label(format!("{}\n{}", self.calendar.season(), self.calendar.time_of_day()));
+            });
+
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.tab, Tab::Cats, "Cats");
+                ui.selectable_value(&mut self.tab, Tab::Dashboard, "Dashboard");
+                ui.selectable_value(&mut self.tab, Tab::Inventory, "Inventory");
+                ui.selectable_value(&mut self.tab, Tab::Ledger, "Ledger");
+                ui.selectable_value(&mut self.tab, Tab::Rooms, "Rooms");
+                ui.selectable_value(&mut self.tab, Tab::Staff, format!("Staff ({})", self.staff.members.len()));
+                ui.selectable_value(&mut self.tab, Tab::Volunteers, format!("Volunteers ({})", self.volunteers.roster.len()));
+                ui.selectable_value(&mut self.tab, Tab::Rules, "Rules");
+                ui.selectable_value(&mut self.tab, Tab::History, "History");
+                ui.selectable_value(&mut self.tab, Tab::Adoption, format!("Adoption ({})", self.adoption.adopters.len()));
+            });
+            ui.separator();
+
+            match self.tab {
+                Tab::Cats => {
+                    self.columns = 0;
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.columns(8, |columns| {
+                            for cat in 0..self.cat_vec.len() {
+                                let id = self.cat_vec[cat].id;
+                                columns[self.columns].group(|ui| {
+                                    let image = match &self.cat_vec[cat].cat_image_byte {
+                                        Some(path) => ui.add(egui::Image::new(format!("file://{}", path)).rounding(10.0)),
+                                        None => ui.add(egui::Label::new("(no picture)").sense(egui::Sense::click())),
+                                    };
+                                    image.context_menu(|ui| {
+                                        ui.menu_button("Feed", |ui| {
+                                            for kind in FoodKind::ALL {
+                                                let count = self.inventory.count(kind);
+                                                let unsuitable = kind.unsuitable_for(&self.cat_vec[cat]);
+                                                let spoiled = self.inventory.next_spoiled(kind, self.calendar.date());
+                                                let mut button = Button::new(format!("{} ({})", kind, count));
+                                                if spoiled {
+                                                    button = button.fill(egui::Color32::from_rgb(110, 30, 30));
+                                                } else if unsuitable.is_some() {
+                                                    button = button.fill(egui::Color32::from_rgb(90, 60, 20));
+                                                }
+                                                let mut response = ui.add_enabled(count > 0, button);
+                                                if spoiled {
+                                                    response = response.on_hover_text("next portion is spoiled");
+                                                } else if let Some(reason) = unsuitable {
+                                                    response = response.on_hover_text(reason);
+                                                }
+                                                if response.clicked() {
+                                                    self.perform(Input::Feed(id, kind), &mut toasts);
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        });
+                                        ui.menu_button("Play", |ui| {
+                                            for kind in ToyKind::ALL {
+                                                let count = self.inventory.toy_count(kind);
+                                                let preference = kind.preference(&self.cat_vec[cat]);
+                                                let response = ui
+                                                    .add_enabled(count > 0, Button::new(format!("{} ({})", kind, count)))
+                                                    .on_hover_text(format!("Preference: {:.0}%", preference * 100.0));
+                                                if response.clicked() {
+                                                    self.perform(Input::Play(id, kind), &mut toasts);
                                                     ui.close_menu();
                                                 }
-                                                Err(e) => toast(&mut toasts, (e, ToastKind::Warning), 10.0),
                                             }
+                                        });
+                                        if ui.add(Button::new(format!("Groom (coat {:.0})", self.cat_vec[cat].coat))).clicked() {
+                                            self.perform(Input::Groom(id), &mut toasts);
+                                            ui.close_menu();
+                                        }
+                                        if ui.add(Button::new("Pet")).clicked() {
+                                            self.perform(Input::Pet(id), &mut toasts);
+                                            ui.close_menu();
+                                        }
+                                        if ui.add_enabled(!self.cat_vec[cat].sleep, Button::new(format!("Let rest ({})", bool_state!("asleep", "awake", self.cat_vec[cat].sleep)))).clicked() {
+                                            self.perform(Input::Rest(id), &mut toasts);
+                                            ui.close_menu();
+                                        }
+
+                                        if ui.add(Button::new("Details")).clicked() {
+                                            if !self.detail_windows.contains(&id) {
+                                                self.detail_windows.push(id);
+                                            }
+                                            ui.close_menu();
                                         }
+
+                                        ui.menu_button("Rename", |ui| {
+                                            ui.text_edit_singleline(&mut self.rename_draft);
+                                            let name = session::clean_name(&self.rename_draft);
+                                            if ui.add_enabled(!name.is_empty(), Button::new("Rename")).clicked() {
+                                                self.perform(Input::Rename(id, name), &mut toasts);
+                                                self.rename_draft.clear();
+                                                ui.close_menu();
+                                            }
+                                        });
+
+                                        ui.menu_button("Mate with", |ui| {
+                                            for mate_cat in 0..self.cat_vec.len() {
+                                                if self.cat_vec[cat].gender.ne(&self.cat_vec[mate_cat].gender) && ui.button(&self.cat_vec[mate_cat].name).clicked() {
+                                                    self.perform(Input::Mate(id, self.cat_vec[mate_cat].id), &mut toasts);
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        });
+                                    });
+
+                                    if image.hovered() {
+                                        ui.add(egui::Label::new(format!("{}", self.cat_vec[cat])));
+                                    } else {
+                                        ui.add(egui::Label::new(self.cat_vec[cat].minimal_info()));
                                     }
                                 });
-                            });
 
-                            if image.hovered() {
-                                ui.add(egui::Label::new(format!("{}", self.cat_vec[cat])));
-                            } else {
-                                ui.add(egui::Label::new(format!("{}", self.cat_vec[cat].minimal_info())));
+                                self.columns = (self.columns + 1) % 8;
                             }
                         });
-
-                        self.columns = (self.columns + 1) % 8;
-                    }
-                });
-            });
-
-            toasts.show(ctx);
+                    });
+                }
+                Tab::Dashboard => show_dashboard(self, ui),
+                Tab::Inventory => show_inventory(self, ui, &mut toasts),
+                Tab::Ledger => show_ledger(self, ui, &mut toasts),
+                Tab::Adoption => show_adoption(self, ui, &mut toasts),
+                Tab::Rooms => show_rooms(self, ui, &mut toasts),
+                Tab::Staff => show_staff(self, ui, &mut toasts),
+                Tab::Volunteers => show_volunteers(self, ui, &mut toasts),
+                Tab::Rules => show_rules(self, ui, &mut toasts),
+                Tab::History => show_history(self, ui, &mut toasts),
+            }
         });
 
+        show_shop(self, ctx, &mut toasts);
+        show_bank(self, ctx, &mut toasts);
+        show_game_over(self, ctx);
+        show_cat_details(self, ctx);
+        toasts.show(ctx);
         ask_close_app(self, ctx);
         ctx.request_repaint();
     }
@@ -165,6 +1177,656 @@ fn toast(toasts: &mut Toasts, message: (String,ToastKind) , time: f64) { // 5 ba
     });
 }
 
+fn show_dashboard(app: &MyApp, ui: &mut egui::Ui) {
+    let population = Population::from_cats(&app.cat_vec);
+
+    let totals = app.ledger.monthly_totals();
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.heading(format!("Population: {}", population.total));
+        ui.label(format!("Average health: {:.2}\nAverage food: {:.2}", population.avg_health, population.avg_food));
+        ui.separator();
+
+        ui.columns(4, |columns| {
+            distribution(&mut columns[0], "Race", population.by_race.iter().map(|(k, v)| (k.as_str(), *v)), population.total);
+            distribution(&mut columns[1], "Color", population.by_color.iter().map(|(k, v)| (k.as_str(), *v)), population.total);
+            distribution(&mut columns[2], "Gender", population.by_gender.iter().map(|(k, v)| (k.as_str(), *v)), population.total);
+            distribution(&mut columns[3], "Age", population.by_age.iter().copied(), population.total);
+        });
+        ui.separator();
+
+        let rate = app.stats.food_rate();
+        let stock = app.inventory.total();
+        ui.label(format!("Food consumption: {:.2} / day\nFood stock: {}\nStock lasts: {}", rate, stock,
+                         if rate > 0.0 { format!("{:.1} days", stock as f32 / rate) } else { "-".to_string() }));
+
+        ui.separator();
+
+        ui.heading("Monthly");
+        egui::Grid::new("monthly_stats").striped(true).show(ui, |ui| {
+            for title in ["Month", "Intakes", "Births", "Deaths", "Adoptions", "Food used", "Money in", "Money out"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for (key, stats) in app.stats.months.iter().rev() {
+                let (year, month) = key;
+                let money = totals.get(key).copied().unwrap_or_default();
+                ui.label(format!("{:02}/{}", month, year));
+                ui.label(stats.intakes.to_string());
+                ui.label(stats.births.to_string());
+                ui.label(stats.deaths.to_string());
+                ui.label(stats.adoptions.to_string());
+                ui.label(stats.food_used.to_string());
+                ui.label(money.credit.to_string());
+                ui.label(money.debit.to_string());
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        let money: Vec<f32> = app.stats.samples.iter().map(|s| s.money as f32).collect();
+        let food: Vec<f32> = app.stats.samples.iter().map(|s| s.food_stock as f32).collect();
+        chart::line_chart(ui, "Money", &money, egui::Color32::GOLD);
+        chart::line_chart(ui, "Food stock", &food, egui::Color32::LIGHT_BLUE);
+    });
+}
+
+fn show_inventory(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    let today = app.calendar.date();
+    if ui.add(Button::new("Discard spoiled food")).clicked() {
+        app.perform(Input::DiscardSpoiled, toasts);
+    }
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for supply in Supply::ALL {
+            ui.label(format!("{}: {}", supply, app.inventory.supply_count(supply)));
+        }
+        ui.separator();
+        for kind in ToyKind::ALL {
+            let durability: Vec<String> = app.inventory.toys().iter().filter(|toy| toy.kind == kind).map(|toy| toy.durability.to_string()).collect();
+            ui.label(format!("{}: {} (uses left: {})", kind, durability.len(), durability.join(", ")));
+        }
+        ui.separator();
+        for stack in app.inventory.stacks() {
+            ui.add(egui::ProgressBar::new(stack.quantity as f32 / stack.capacity as f32)
+                .text(format!("#{} {}: {} / {}", stack.id, stack.kind, stack.quantity, stack.capacity)));
+            for batch in &stack.batches {
+                let text = format!("  {} portions, bought {}, expires {}", batch.quantity, batch.purchased, batch.expires);
+                if batch.is_spoiled(today) {
+                    ui.colored_label(egui::Color32::RED, format!("{} (spoiled)", text));
+                } else if batch.is_expiring_soon(today) {
+                    ui.colored_label(egui::Color32::YELLOW, format!("{} (expiring soon)", text));
+                } else {
+                    ui.label(text);
+                }
+            }
+        }
+    });
+}
+
+fn show_ledger(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Balance: {}", app.money()));
+        egui::ComboBox::from_label("Category")
+            .selected_text(app.ledger_filter.map_or("All".to_string(), |category| category.to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut app.ledger_filter, None, "All");
+                for category in Category::ALL {
+                    ui.selectable_value(&mut app.ledger_filter, Some(category), category.to_string());
+                }
+            });
+        if ui.add(Button::new("Export CSV")).clicked() {
+            let path = std::path::Path::new("ledger.csv");
+            match app.ledger.export_csv(path) {
+                Ok(()) => toast(toasts, (format!("Ledger exported to {}", path.display()), ToastKind::Success), 10.0),
+                Err(e) => toast(toasts, (format!("Can't export ledger: {}", e), ToastKind::Error), 10.0),
+            }
+        }
+    });
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Monthly totals");
+        egui::Grid::new("ledger_months").striped(true).show(ui, |ui| {
+            for title in ["Month", "Credit", "Debit", "Net"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for ((year, month), totals) in app.ledger.monthly_totals().iter().rev() {
+                ui.label(format!("{:02}/{}", month, year));
+                ui.label(totals.credit.to_string());
+                ui.label(totals.debit.to_string());
+                ui.label((totals.credit as i64 - totals.debit as i64).to_string());
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.heading("Transactions");
+        egui::Grid::new("ledger_entries").striped(true).show(ui, |ui| {
+            for title in ["Date", "Category", "Amount", "Label", "Cat"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for entry in app.ledger.entries().iter().rev().filter(|entry| app.ledger_filter.is_none_or(|category| entry.category == category)) {
+                ui.label(entry.date.format("%d/%m/%Y %Hh").to_string());
+                ui.label(entry.category.to_string());
+                let color = if entry.amount >= 0 { egui::Color32::LIGHT_GREEN } else { egui::Color32::LIGHT_RED };
+                ui.colored_label(color, format!("{:+}", entry.amount));
+                ui.label(&entry.label);
+                ui.label(entry.cat_id.map_or(String::new(), |id| format!("#{}", id)));
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn show_rooms(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    let mut moved = None;
+    let mut cleaned = None;
+    ui.label(format!("Litter bags: {}", app.inventory.supply_count(Supply::Litter)));
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.columns(3, |columns| {
+            for room in &app.rooms {
+                let ui = &mut columns[room.id % 3];
+                let occupants = room::occupancy(room, &app.cat_vec);
+                let (_, dropped) = ui.dnd_drop_zone::<u64, ()>(egui::Frame::group(ui.style()), |ui| {
+                    ui.set_min_width(ui.available_width());
+                    let title = format!("{} ({}) {}/{}", room.name, room.kind, occupants, room.capacity);
+                    if occupants > room.capacity {
+                        ui.colored_label(egui::Color32::RED, title);
+                    } else {
+                        ui.strong(title);
+                    }
+                    ui.horizontal(|ui| {
+                        let color = if room.is_dirty() { egui::Color32::from_rgb(140, 90, 30) } else { egui::Color32::DARK_GREEN };
+                        ui.add(egui::ProgressBar::new(room.litter / 100.0).fill(color).text(format!("Litter {:.0}%", room.litter)).desired_width(150.0));
+                        if ui.add_enabled(app.inventory.supply_count(Supply::Litter) > 0, Button::new("Clean")).clicked() {
+                            cleaned = Some(room.id);
+                        }
+                    });
+                    for cat in app.cat_vec.iter().filter(|cat| cat.room == room.id) {
+                        let id = egui::Id::new(("room_cat", cat.id));
+                        ui.dnd_drag_source(id, cat.id, |ui| {
+                            let mut text = format!("{} #{} stress {:.0}", cat.name, cat.id, cat.stress);
+                            if cat.sick {
+                                text.push_str(" (sick)");
+                            }
+                            if let Some(until) = cat.quarantine_until {
+                                text.push_str(&format!(" (quarantine until {})", until));
+                            }
+                            ui.label(text);
+                        });
+                    }
+                });
+                if let Some(cat_id) = dropped {
+                    moved = Some((*cat_id, room.id));
+                }
+                ui.add_space(8.0);
+            }
+        });
+    });
+
+    if let Some(room_id) = cleaned {
+        app.perform(Input::Clean(room_id), toasts);
+    }
+    if let Some((cat_id, room_id)) = moved {
+        app.perform(Input::Move(cat_id, room_id), toasts);
+    }
+}
+
+fn show_staff(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    let mut inputs = vec![];
+    ui.label(format!("Daily salaries: {}", app.staff.daily_salaries()));
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Team");
+        egui::Grid::new("staff_grid").striped(true).show(ui, |ui| {
+            for header in ["Name", "Role", "Skill", "Salary / day", "Shift", "Room", ""] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for member in &app.staff.members {
+                ui.label(member.name);
+                ui.label(member.role.to_string());
+                ui.label(member.skill.to_string());
+                ui.label(member.daily_salary().to_string());
+                egui::ComboBox::from_id_source(("staff_shift", member.id))
+                    .selected_text(member.shift.to_string())
+                    .show_ui(ui, |ui| {
+                        for shift in Shift::ALL {
+                            if ui.selectable_label(member.shift == shift, shift.to_string()).clicked() {
+                                inputs.push(Input::SetShift(member.id, shift));
+                            }
+                        }
+                    });
+                let room_name = member.room.map_or("Whole shelter", |room| app.rooms[room].name);
+                egui::ComboBox::from_id_source(("staff_room", member.id))
+                    .selected_text(room_name)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(member.room.is_none(), "Whole shelter").clicked() {
+                            inputs.push(Input::Assign(member.id, None));
+                        }
+                        for room in &app.rooms {
+                            if ui.selectable_label(member.room == Some(room.id), room.name).clicked() {
+                                inputs.push(Input::Assign(member.id, Some(room.id)));
+                            }
+                        }
+                    });
+                if ui.add(Button::new("Fire")).clicked() {
+                    inputs.push(Input::Fire(member.id));
+                }
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.heading("Candidates");
+        egui::Grid::new("candidates_grid").striped(true).show(ui, |ui| {
+            for candidate in &app.staff.candidates {
+                ui.label(candidate.to_string());
+                ui.label(candidate.shift.to_string());
+                ui.label(format!("{} / day", candidate.daily_salary()));
+                if ui.add(Button::new("Hire")).clicked() {
+                    inputs.push(Input::Hire(candidate.id));
+                }
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.heading("Log");
+        for (date, text) in app.staff.log.iter().rev() {
+            ui.label(format!("{} {}", date.format("%d/%m/%Y %Hh"), text));
+        }
+    });
+
+    for input in inputs {
+        app.perform(input, toasts);
+    }
+}
+
+fn show_volunteers(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    let mut removed = None;
+    if ui.add_enabled(app.volunteers.roster.len() < volunteer::MAX_VOLUNTEERS, Button::new("Recruit a volunteer")).clicked() {
+        app.perform(Input::Recruit, toasts);
+    }
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Roster");
+        egui::Grid::new("volunteers_grid").striped(true).show(ui, |ui| {
+            for header in ["Name", "Availability", "Reliability", "Sessions", "No-shows", ""] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for volunteer in &app.volunteers.roster {
+                ui.label(volunteer.name);
+                let slots: Vec<String> = volunteer.availability.iter().map(|(weekday, shift)| format!("{} {}", weekday, shift)).collect();
+                ui.label(slots.join("\n"));
+                ui.label(volunteer.attendance().map_or("-".to_string(), |attendance| format!("{:.0}%", attendance * 100.0)));
+                ui.label(volunteer.sessions.to_string());
+                ui.label(volunteer.no_shows.to_string());
+                if ui.add(Button::new("Remove")).clicked() {
+                    removed = Some(volunteer.id);
+                }
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.heading("Weekly coverage");
+        egui::Grid::new("coverage_grid").striped(true).show(ui, |ui| {
+            ui.label("");
+            for weekday in WEEK {
+                ui.strong(weekday.to_string());
+            }
+            ui.end_row();
+            for shift in VOLUNTEER_SHIFTS {
+                ui.strong(shift.to_string());
+                for weekday in WEEK {
+                    let names = app.volunteers.coverage(weekday, shift);
+                    if names.is_empty() {
+                        ui.colored_label(egui::Color32::RED, "No one");
+                    } else {
+                        ui.label(names.join("\n"));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+
+    if let Some(id) = removed {
+        app.perform(Input::Dismiss(id), toasts);
+    }
+}
+
+fn show_rules(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    let mut inputs = vec![];
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Rules");
+        egui::Grid::new("rules_grid").striped(true).show(ui, |ui| {
+            for rule in &app.rules.rules {
+                let mut enabled = rule.enabled;
+                if ui.checkbox(&mut enabled, format!("#{}", rule.id)).changed() {
+                    inputs.push(Input::ToggleRule(rule.id));
+                }
+                ui.label(rule.to_string());
+                if ui.add(Button::new("Delete")).clicked() {
+                    inputs.push(Input::RemoveRule(rule.id));
+                }
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.heading("New rule");
+        let draft = &mut app.rule_draft;
+        let mut dropped = None;
+        for (index, condition) in draft.conditions.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(if index == 0 { "if" } else { "and" });
+                egui::ComboBox::from_id_source(("rule_subject", index))
+                    .selected_text(condition.subject.to_string())
+                    .show_ui(ui, |ui| {
+                        for subject in Subject::all() {
+                            if ui.selectable_value(&mut condition.subject, subject, subject.to_string()).changed() {
+                                condition.comparison = subject.comparisons()[0];
+                            }
+                        }
+                    });
+                egui::ComboBox::from_id_source(("rule_comparison", index))
+                    .selected_text(condition.comparison.to_string())
+                    .show_ui(ui, |ui| {
+                        for comparison in condition.subject.comparisons() {
+                            ui.selectable_value(&mut condition.comparison, *comparison, comparison.to_string());
+                        }
+                    });
+                if !condition.subject.is_flag() {
+                    ui.add(egui::DragValue::new(&mut condition.value));
+                }
+                if ui.add(Button::new("x")).clicked() {
+                    dropped = Some(index);
+                }
+            });
+        }
+        if let Some(index) = dropped {
+            draft.conditions.remove(index);
+        }
+        if ui.add(Button::new("Add condition")).clicked() {
+            draft.conditions.push(Condition { subject: Subject::Food, comparison: Comparison::Below, value: 20.0 });
+        }
+        ui.horizontal(|ui| {
+            ui.label("then");
+            egui::ComboBox::from_id_source("rule_action")
+                .selected_text(draft.action.to_string())
+                .show_ui(ui, |ui| {
+                    for action in Action::templates() {
+                        let selected = match (action, draft.action) {
+                            (Action::Buy(a, _), Action::Buy(b, _)) => a == b,
+                            _ => action == draft.action,
+                        };
+                        if ui.selectable_label(selected, action.to_string()).clicked() {
+                            draft.action = action;
+                        }
+                    }
+                });
+            if let Action::Buy(_, quantity) = &mut draft.action {
+                ui.add(egui::DragValue::new(quantity).range(1..=5000));
+            }
+        });
+        if ui.add(Button::new("Add rule")).clicked() {
+            inputs.push(Input::AddRule(draft.clone()));
+            draft.conditions.clear();
+        }
+        ui.separator();
+
+        ui.heading("Execution log");
+        for (date, text) in app.rules.log.iter().rev() {
+            ui.label(format!("{} {}", date.format("%d/%m/%Y %Hh"), text));
+        }
+    });
+
+    for input in inputs {
+        app.perform(input, toasts);
+    }
+}
+
+fn show_history(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!app.history.done.is_empty(), Button::new("Undo (Ctrl+Z)")).clicked() {
+            app.perform(Input::Undo, toasts);
+        }
+        if ui.add_enabled(!app.history.undone.is_empty(), Button::new("Redo (Ctrl+Shift+Z)")).clicked() {
+            app.perform(Input::Redo, toasts);
+        }
+    });
+    if app.recorder.is_some() {
+        ui.label(format!("Seed {}, session recorded to {} (replay with --replay {})", app.seed, SESSION_LOG, SESSION_LOG));
+    } else {
+        ui.label(format!("Seed {}, session not recorded", app.seed));
+    }
+    ui.separator();
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for command in app.history.undone.iter() {
+            ui.colored_label(egui::Color32::GRAY, format!("{} {} (undone)", command.date.format("%d/%m/%Y %Hh"), command.label));
+        }
+        for command in app.history.done.iter().rev() {
+            ui.label(format!("{} {}", command.date.format("%d/%m/%Y %Hh"), command.label));
+        }
+    });
+}
+
+fn show_adoption(app: &mut MyApp, ui: &mut egui::Ui, toasts: &mut Toasts) {
+    if app.adoption.adopters.is_empty() {
+        ui.label("No adopter is waiting.");
+    }
+    let mut adopt = None;
+    ui.columns(2, |columns| {
+        egui::ScrollArea::vertical().id_source("adopters").show(&mut columns[0], |ui| {
+            for adopter in &app.adoption.adopters {
+                let selected = app.selected_adopter == Some(adopter.id);
+                if ui.selectable_label(selected, adopter.to_string()).clicked() {
+                    app.selected_adopter = Some(adopter.id);
+                }
+                ui.separator();
+            }
+            if !app.adoption.placements.is_empty() {
+                ui.strong("Trial period");
+                for placement in &app.adoption.placements {
+                    ui.label(format!("{} with {} since {} ({}%)", placement.cat.name, placement.adopter, placement.date, placement.score));
+                }
+            }
+        });
+
+        let Some(adopter) = app.adoption.adopters.iter().find(|adopter| Some(adopter.id) == app.selected_adopter) else {
+            columns[1].label("Select an adopter to see the matching cats.");
+            return;
+        };
+        let mut matches: Vec<(u32, &CatInfo)> = app.cat_vec.iter().map(|cat| (adopter.compatibility(cat), cat)).collect();
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        egui::ScrollArea::vertical().id_source("matches").show(&mut columns[1], |ui| {
+            egui::Grid::new("adoption_matches").striped(true).show(ui, |ui| {
+                for (score, cat) in matches {
+                    let mut label = format!("{} #{} ({}, {} an(s), {})", cat.name, cat.id, cat.race, cat.age, cat.gender);
+                    for partner in app.relationships.bonded_with(cat.id) {
+                        if let Some(partner) = app.cat_vec.iter().find(|other| other.id == partner) {
+                            label.push_str(&format!("\n  bonded with {}, adopted together", partner.name));
+                        }
+                    }
+                    ui.label(label);
+                    ui.add(egui::ProgressBar::new(score as f32 / 100.0).text(format!("{}%", score)).desired_width(150.0));
+                    ui.label(format!("Fee: {}", economy::adoption_fee(cat)));
+                    if ui.button("Adopt").clicked() {
+                        adopt = Some((adopter.id, cat.id));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    });
+
+    if let Some((adopter_id, cat_id)) = adopt {
+        app.perform(Input::Adopt(adopter_id, cat_id), toasts);
+        app.selected_adopter = None;
+    }
+}
+
+fn distribution<'a>(ui: &mut egui::Ui, title: &str, values: impl Iterator<Item = (&'a str, usize)>, total: usize) {
+    ui.strong(title);
+    for (name, count) in values {
+        let ratio = if total > 0 { count as f32 / total as f32 } else { 0.0 };
+        ui.add(egui::ProgressBar::new(ratio).text(format!("{}: {}", name, count)));
+    }
+}
+
+fn show_shop(app: &mut MyApp, ctx: &egui::Context, toasts: &mut Toasts) {
+    let mut open = app.show_shop;
+    egui::Window::new("Shop")
+        .open(&mut open)
+        .enabled(app.game_over.is_none())
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!("Money: {}", app.money()));
+            ui.separator();
+            egui::Grid::new("shop_offers").striped(true).show(ui, |ui| {
+                for (index, offer) in OFFERS.iter().enumerate() {
+                    ui.label(format!("{} x{}", offer.product, offer.quantity));
+                    ui.label(format!("{}", offer.price));
+                    if ui.add_enabled(app.can_afford(offer.price), Button::new("Buy")).clicked() {
+                        app.perform(Input::Buy(index), toasts);
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+
+            let selected = app.vet_cat.and_then(|id| app.cat_vec.iter().find(|cat| cat.id == id)).map(|cat| format!("{} #{}", cat.name, cat.id));
+            egui::ComboBox::from_label("Cat")
+                .selected_text(selected.as_deref().unwrap_or("-"))
+                .show_ui(ui, |ui| {
+                    for cat in app.cat_vec.iter() {
+                        ui.selectable_value(&mut app.vet_cat, Some(cat.id), format!("{} #{}", cat.name, cat.id));
+                    }
+                });
+            for service in VetService::ALL {
+                let enabled = selected.is_some() && app.can_afford(service.price());
+                if ui.add_enabled(enabled, Button::new(format!("{} ({})", service, service.price()))).clicked() {
+                    if let Some(cat_id) = app.vet_cat {
+                        app.perform(Input::Vet(service, cat_id), toasts);
+                    }
+                }
+            }
+        });
+    app.show_shop = open;
+}
+
+fn show_bank(app: &mut MyApp, ctx: &egui::Context, toasts: &mut Toasts) {
+    let mut open = app.show_bank;
+    egui::Window::new("Bank")
+        .open(&mut open)
+        .enabled(app.game_over.is_none())
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!("Balance: {}\nOverdraft limit: {}\nDebt: {}\nMonths in default: {}",
+                             app.money(), -OVERDRAFT_LIMIT, app.bank.debt(), app.bank.months_in_default));
+            ui.separator();
+
+            ui.strong("Loans");
+            let mut repay = None;
+            egui::Grid::new("bank_loans").striped(true).show(ui, |ui| {
+                for loan in &app.bank.loans {
+                    ui.label(format!("#{} taken {}", loan.id, loan.taken));
+                    ui.label(format!("{} / {} at {:.1}% / month", loan.remaining, loan.principal, loan.monthly_rate * 100.0));
+                    if ui.add_enabled(app.money() >= loan.remaining as i64, Button::new("Repay")).clicked() {
+                        repay = Some(loan.id);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(loan_id) = repay {
+                app.perform(Input::Repay(loan_id), toasts);
+            }
+            ui.separator();
+
+            ui.strong("Borrow");
+            for (index, (amount, rate)) in LOAN_OFFERS.into_iter().enumerate() {
+                if ui.add_enabled(app.bank.can_borrow(amount), Button::new(format!("{} at {:.1}% / month", amount, rate * 100.0))).clicked() {
+                    app.perform(Input::Borrow(index), toasts);
+                }
+            }
+        });
+    app.show_bank = open;
+}
+
+fn show_game_over(app: &mut MyApp, ctx: &egui::Context) {
+    let Some(message) = &app.game_over else {
+        return;
+    };
+    egui::Window::new("Administrator takeover")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, (0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(message);
+            if ui.button("Quit").clicked() {
+                app.allowed_to_close = true;
+                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        });
+}
+
+fn show_cat_details(app: &mut MyApp, ctx: &egui::Context) {
+    let cats = &app.cat_vec;
+    let income = &app.income;
+    let rooms = &app.rooms;
+    let relationships = &app.relationships;
+    app.detail_windows.retain(|id| {
+        let Some(cat) = cats.iter().find(|cat| cat.id == *id) else {
+            return false;
+        };
+        let mut open = true;
+        egui::Window::new(format!("{} #{}", cat.name, cat.id))
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.label(cat.to_string());
+                ui.label(format!("Room: {}", rooms[cat.room].name));
+                ui.label(format!("Adoption fee: {}", economy::adoption_fee(cat)));
+                if let Some(sponsorship) = income.sponsor_of(cat.id) {
+                    ui.label(format!("Sponsored by {} ({} / month)", sponsorship.sponsor, sponsorship.monthly));
+                }
+                if let (Some(first), Some(last)) = (cat.history.front(), cat.history.back()) {
+                    ui.label(format!("History: {} -> {}", first.date.format("%d/%m/%Y %Hh"), last.date.format("%d/%m/%Y %Hh")));
+                }
+                ui.separator();
+                let weight: Vec<f32> = cat.history.iter().map(|r| r.weight).collect();
+                let health: Vec<f32> = cat.history.iter().map(|r| r.health).collect();
+                let food: Vec<f32> = cat.history.iter().map(|r| r.food).collect();
+                let mood: Vec<f32> = cat.history.iter().map(|r| r.mood).collect();
+                let energy: Vec<f32> = cat.history.iter().map(|r| r.energy).collect();
+                let coat: Vec<f32> = cat.history.iter().map(|r| r.coat).collect();
+                chart::line_chart(ui, "Weight (kg)", &weight, egui::Color32::LIGHT_BLUE);
+                chart::line_chart(ui, "Health", &health, egui::Color32::LIGHT_GREEN);
+                chart::line_chart(ui, "Food", &food, egui::Color32::GOLD);
+                chart::line_chart(ui, "Mood", &mood, egui::Color32::LIGHT_RED);
+                chart::line_chart(ui, "Energy", &energy, egui::Color32::KHAKI);
+                chart::line_chart(ui, "Coat", &coat, egui::Color32::LIGHT_YELLOW);
+                ui.separator();
+
+                let links: Vec<(String, f32)> = relationships.of(cat.id).into_iter()
+                    .filter_map(|(other, affinity)| cats.iter().find(|c| c.id == other).map(|other| (other.name.to_string(), affinity)))
+                    .collect();
+                ui.label("Relationships");
+                for (name, affinity) in &links {
+                    ui.label(format!("- {}: {} ({:.0})", name, Relation::from_affinity(*affinity), affinity));
+                }
+                chart::relationship_graph(ui, &cat.name, &links);
+            });
+        open
+    });
+}
 
 fn ask_close_app(app: &mut MyApp, ctx: &egui::Context) {
     if ctx.input(|i| i.viewport().close_requested()) && !app.allowed_to_close {
@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+use rand::{distributions::{Distribution, Standard}, Rng};
+use crate::rng::{random, sim_rng};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Personality {
+    Shy,
+    Playful,
+    Cuddly,
+    Aggressive,
+    Lazy,
+}
+
+/// Most personality traits a cat can have.
+pub const MAX_TRAITS: usize = 2;
+
+impl Distribution<Personality> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Personality {
+        match rng.gen_range(0..=4) {
+            0 => Personality::Shy,
+            1 => Personality::Playful,
+            2 => Personality::Cuddly,
+            3 => Personality::Aggressive,
+            4 => Personality::Lazy,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Display for Personality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Personality::Shy => write!(f, "Timide"),
+            Personality::Playful => write!(f, "Joueur"),
+            Personality::Cuddly => write!(f, "Câlin"),
+            Personality::Aggressive => write!(f, "Agressif"),
+            Personality::Lazy => write!(f, "Paresseux"),
+        }
+    }
+}
+
+fn push_unique(traits: &mut Vec<Personality>, personality: Personality) {
+    if traits.len() < MAX_TRAITS && !traits.contains(&personality) {
+        traits.push(personality);
+    }
+}
+
+/// One or two random traits for a newly spawned cat.
+pub fn random_traits() -> Vec<Personality> {
+    let mut traits = vec![random()];
+    if sim_rng().gen_bool(0.5) {
+        push_unique(&mut traits, random());
+    }
+    traits
+}
+
+/// Traits of a kitten: each parent trait is passed on half of the time, with a chance of a new one.
+pub fn inherit(mother: &[Personality], father: &[Personality]) -> Vec<Personality> {
+    let mut rng = sim_rng();
+    let mut traits = vec![];
+    for personality in mother.iter().chain(father) {
+        if rng.gen_bool(0.5) {
+            push_unique(&mut traits, *personality);
+        }
+    }
+    if traits.is_empty() || rng.gen_bool(0.25) {
+        push_unique(&mut traits, random());
+    }
+    traits
+}
+
+pub fn format_traits(traits: &[Personality]) -> String {
+    traits.iter().map(|personality| personality.to_string()).collect::<Vec<_>>().join(", ")
+}
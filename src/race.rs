@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter};
 use rand::{distributions::{Distribution, Standard}, Rng};
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub enum Race {
     #[default]
     Persan,
@@ -25,6 +25,38 @@ pub enum Race {
     Korat,
 }
 
+impl Race {
+    /// How energetic the breed usually is, from 1 (placid) to 5 (very active).
+    pub fn activity_level(&self) -> u8 {
+        match self {
+            Race::Bengal | Race::Abyssin | Race::Siamois | Race::Oriental => 5,
+            Race::Sphynx | Race::AngoraTurc | Race::Burmese | Race::Korat => 4,
+            Race::MaineCoon | Race::Norvegien | Race::Europeen | Race::Manx | Race::AmericanShorthair => 3,
+            Race::Birman | Race::ScottishFold | Race::Chartreux => 2,
+            Race::Persan | Race::Ragdoll | Race::ExoticShorthair => 1,
+        }
+    }
+
+    /// Coat condition lost every tick without brushing; long coats mat much faster.
+    pub fn coat_wear(&self) -> f32 {
+        match self {
+            Race::Persan | Race::MaineCoon | Race::Ragdoll | Race::Norvegien | Race::AngoraTurc | Race::Birman => 1.5,
+            Race::Sphynx => 0.0,
+            _ => 0.4,
+        }
+    }
+
+    /// Extra adoption fee asked for sought-after breeds.
+    pub fn breed_premium(&self) -> u64 {
+        match self {
+            Race::Bengal | Race::MaineCoon | Race::Sphynx | Race::Ragdoll | Race::ScottishFold => 150,
+            Race::Persan | Race::Siamois | Race::Birman | Race::Norvegien | Race::Abyssin => 80,
+            Race::Europeen => 0,
+            _ => 40,
+        }
+    }
+}
+
 impl Distribution<Race> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Race {
         match rng.gen_range(0..=18) {
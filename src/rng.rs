@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+use rand::{distributions::{Distribution, Standard}, rngs::StdRng, Error, Rng, RngCore, SeedableRng};
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::seed_from_u64(0));
+}
+
+/// Restarts the simulation RNG from `seed`: the same seed and the same inputs replay the same session.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Handle on the seeded simulation RNG, used everywhere instead of `thread_rng`.
+#[derive(Clone, Copy)]
+pub struct SimRng;
+
+pub fn sim_rng() -> SimRng {
+    SimRng
+}
+
+/// Like `rand::random`, but drawn from the simulation RNG.
+pub fn random<T>() -> T
+where
+    Standard: Distribution<T>,
+{
+    sim_rng().gen()
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Duration, NaiveDate};
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::cat::CatInfo;
+use crate::personality::Personality;
+
+/// Days a new intake spends in quarantine before joining the other cats.
+pub const QUARANTINE_DAYS: i64 = 14;
+/// Litter level added to a room's boxes by every meal eaten there.
+pub const LITTER_PER_MEAL: f32 = 3.0;
+/// Litter level above which a room is dirty and cats start to suffer.
+pub const DIRTY_LITTER: f32 = 60.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RoomKind {
+    Nursery,
+    Quarantine,
+    Adult,
+    Senior,
+    Infirmary,
+}
+
+impl Display for RoomKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoomKind::Nursery => write!(f, "Nursery"),
+            RoomKind::Quarantine => write!(f, "Quarantine"),
+            RoomKind::Adult => write!(f, "Adult room"),
+            RoomKind::Senior => write!(f, "Senior room"),
+            RoomKind::Infirmary => write!(f, "Infirmary"),
+        }
+    }
+}
+
+pub struct Room {
+    pub id: usize,
+    pub kind: RoomKind,
+    pub name: &'static str,
+    pub capacity: usize,
+    /// How full the litter boxes are, from 0 (clean) to 100.
+    pub litter: f32,
+}
+
+impl Room {
+    pub fn is_dirty(&self) -> bool {
+        self.litter > DIRTY_LITTER
+    }
+
+    pub fn soil(&mut self, amount: f32) {
+        self.litter = (self.litter + amount).min(100.0);
+    }
+
+    pub fn clean(&mut self) {
+        self.litter = 0.0;
+    }
+
+    /// Whether `cat` may be placed in this room; cats in quarantine can only stay there or go to the infirmary.
+    pub fn accepts(&self, cat: &CatInfo, today: NaiveDate) -> Result<(), String> {
+        if cat.in_quarantine(today) && !matches!(self.kind, RoomKind::Quarantine | RoomKind::Infirmary) {
+            return Err(format!("{} is in quarantine until {}", cat.name, cat.quarantine_until.unwrap()));
+        }
+        Ok(())
+    }
+}
+
+pub fn default_rooms() -> Vec<Room> {
+    [
+        (RoomKind::Nursery, "Nursery", 8),
+        (RoomKind::Quarantine, "Quarantine", 6),
+        (RoomKind::Adult, "Adults A", 12),
+        (RoomKind::Adult, "Adults B", 12),
+        (RoomKind::Senior, "Seniors", 8),
+        (RoomKind::Infirmary, "Infirmary", 4),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(id, (kind, name, capacity))| Room { id, kind, name, capacity, litter: 0.0 })
+    .collect()
+}
+
+pub fn occupancy(room: &Room, cats: &[CatInfo]) -> usize {
+    cats.iter().filter(|cat| cat.room == room.id).count()
+}
+
+/// Picks the room matching the cat's age and condition, preferring rooms with free space.
+pub fn pick_room(rooms: &[Room], cats: &[CatInfo], cat: &CatInfo, today: NaiveDate) -> usize {
+    let kind = if cat.in_quarantine(today) {
+        RoomKind::Quarantine
+    } else if cat.sick {
+        RoomKind::Infirmary
+    } else if cat.age <= 1 {
+        RoomKind::Nursery
+    } else if cat.age >= 11 {
+        RoomKind::Senior
+    } else {
+        RoomKind::Adult
+    };
+    let candidates: Vec<&Room> = rooms.iter().filter(|room| room.kind == kind).collect();
+    candidates
+        .iter()
+        .find(|room| occupancy(room, cats) < room.capacity)
+        .or(candidates.first())
+        .map_or(0, |room| room.id)
+}
+
+/// Raises stress in crowded rooms, lets it settle elsewhere and spreads illness between roommates.
+/// Returns the names of newly infected cats.
+pub fn update_rooms(rooms: &[Room], cats: &mut [CatInfo]) -> Vec<String> {
+    let mut rng = sim_rng();
+    let mut infected = vec![];
+    for room in rooms {
+        let occupants = occupancy(room, cats);
+        let crowding = occupants as f32 / room.capacity as f32;
+        let sick = cats.iter().filter(|cat| cat.room == room.id && cat.sick).count();
+        let aggressive = cats.iter().filter(|cat| cat.room == room.id && cat.has(Personality::Aggressive)).count();
+        let dirt = if room.is_dirty() { 1.0 + (room.litter - DIRTY_LITTER) / 20.0 } else { 1.0 };
+        let spread = (0.02 * sick as f64 * crowding.max(1.0) as f64 * dirt as f64).min(1.0);
+
+        for cat in cats.iter_mut().filter(|cat| cat.room == room.id) {
+            if crowding > 1.0 {
+                cat.stress = (cat.stress + 10.0 * (crowding - 1.0)).min(100.0);
+            } else {
+                cat.stress = (cat.stress - 2.0).max(0.0);
+            }
+            let bullies = aggressive - cat.has(Personality::Aggressive) as usize;
+            let mut pressure = 2.0 * bullies as f32;
+            if cat.has(Personality::Shy) {
+                pressure += occupants.saturating_sub(4) as f32;
+            }
+            cat.stress = (cat.stress + pressure).min(100.0);
+            if room.kind != RoomKind::Infirmary && !cat.sick && rng.gen_bool(spread) {
+                cat.sick = true;
+                infected.push(cat.name.clone());
+            }
+        }
+    }
+    infected
+}
+
+pub fn quarantine_end(today: NaiveDate) -> NaiveDate {
+    today + Duration::days(QUARANTINE_DAYS)
+}
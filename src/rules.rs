@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use chrono::NaiveDateTime;
+use crate::cat::CatInfo;
+use crate::inventory::{FoodKind, Inventory};
+use crate::toy::ToyKind;
+
+/// Rule executions kept in the log.
+pub const LOG_LEN: usize = 200;
+
+/// What a condition looks at: a stat of the cat, a state of the cat, or the stock of a food.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Subject {
+    Food,
+    Health,
+    Mood,
+    Energy,
+    Stress,
+    Coat,
+    Awake,
+    Sick,
+    Stock(FoodKind),
+}
+
+impl Subject {
+    pub fn all() -> Vec<Subject> {
+        let mut subjects = vec![
+            Subject::Food, Subject::Health, Subject::Mood, Subject::Energy, Subject::Stress, Subject::Coat, Subject::Awake, Subject::Sick,
+        ];
+        subjects.extend(FoodKind::ALL.map(Subject::Stock));
+        subjects
+    }
+
+    /// Whether the subject is true or false rather than a number.
+    pub fn is_flag(&self) -> bool {
+        matches!(self, Subject::Awake | Subject::Sick)
+    }
+
+    pub fn comparisons(&self) -> &'static [Comparison] {
+        if self.is_flag() { &[Comparison::Is, Comparison::IsNot] } else { &[Comparison::Below, Comparison::Above] }
+    }
+
+    fn is_about_cat(&self) -> bool {
+        !matches!(self, Subject::Stock(_))
+    }
+
+    /// The subject's value, flags being 1 when true; cat subjects without a cat have none.
+    fn value(&self, cat: Option<&CatInfo>, inventory: &Inventory) -> Option<f32> {
+        if let Subject::Stock(kind) = self {
+            return Some(inventory.count(*kind) as f32);
+        }
+        let cat = cat?;
+        Some(match self {
+            Subject::Food => cat.food,
+            Subject::Health => cat.health,
+            Subject::Mood => cat.mood,
+            Subject::Energy => cat.energy,
+            Subject::Stress => cat.stress,
+            Subject::Coat => cat.coat,
+            Subject::Awake => (!cat.sleep) as u8 as f32,
+            Subject::Sick => cat.sick as u8 as f32,
+            Subject::Stock(_) => unreachable!(),
+        })
+    }
+}
+
+impl Display for Subject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Subject::Food => write!(f, "food"),
+            Subject::Health => write!(f, "health"),
+            Subject::Mood => write!(f, "mood"),
+            Subject::Energy => write!(f, "energy"),
+            Subject::Stress => write!(f, "stress"),
+            Subject::Coat => write!(f, "coat"),
+            Subject::Awake => write!(f, "awake"),
+            Subject::Sick => write!(f, "sick"),
+            Subject::Stock(kind) => write!(f, "{} stock", kind),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Below,
+    Above,
+    Is,
+    IsNot,
+}
+
+impl Comparison {
+    pub const ALL: [Comparison; 4] = [Comparison::Below, Comparison::Above, Comparison::Is, Comparison::IsNot];
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::Below => write!(f, "<"),
+            Comparison::Above => write!(f, ">"),
+            Comparison::Is => write!(f, "is"),
+            Comparison::IsNot => write!(f, "is not"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Condition {
+    pub subject: Subject,
+    pub comparison: Comparison,
+    /// Threshold for numeric subjects, unused by flags.
+    pub value: f32,
+}
+
+impl Condition {
+    fn holds(&self, cat: Option<&CatInfo>, inventory: &Inventory) -> bool {
+        let Some(value) = self.subject.value(cat, inventory) else {
+            return false;
+        };
+        match self.comparison {
+            Comparison::Below => value < self.value,
+            Comparison::Above => value > self.value,
+            Comparison::Is => value > 0.5,
+            Comparison::IsNot => value < 0.5,
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.comparison {
+            Comparison::Is => write!(f, "{}", self.subject),
+            Comparison::IsNot => write!(f, "not {}", self.subject),
+            _ => write!(f, "{} {} {}", self.subject, self.comparison, self.value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Feed(FoodKind),
+    Play(ToyKind),
+    Groom,
+    Buy(FoodKind, u32),
+}
+
+impl Action {
+    pub fn templates() -> Vec<Action> {
+        let mut actions: Vec<Action> = FoodKind::ALL.map(Action::Feed).into();
+        actions.extend(ToyKind::ALL.map(Action::Play));
+        actions.push(Action::Groom);
+        actions.extend(FoodKind::ALL.map(|kind| Action::Buy(kind, 100)));
+        actions
+    }
+
+    /// Whether the action is done to each matching cat rather than once for the shelter.
+    pub fn is_for_cat(&self) -> bool {
+        !matches!(self, Action::Buy(..))
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Feed(kind) => write!(f, "feed with {}", kind),
+            Action::Play(kind) => write!(f, "play with {}", kind),
+            Action::Groom => write!(f, "groom"),
+            Action::Buy(kind, quantity) => write!(f, "buy {} {}", quantity, kind),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Rule {
+    pub id: u32,
+    pub enabled: bool,
+    pub conditions: Vec<Condition>,
+    pub action: Action,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let conditions: Vec<String> = self.conditions.iter().map(|condition| condition.to_string()).collect();
+        if conditions.is_empty() {
+            write!(f, "always {}", self.action)
+        } else {
+            write!(f, "if {} then {}", conditions.join(" and "), self.action)
+        }
+    }
+}
+
+pub struct Rules {
+    pub rules: Vec<Rule>,
+    pub log: VecDeque<(NaiveDateTime, String)>,
+    next_id: u32,
+}
+
+impl Rules {
+    /// Starts with the usual examples, disabled until the player turns them on.
+    pub fn new() -> Self {
+        let mut rules = Self { rules: vec![], log: VecDeque::new(), next_id: 1 };
+        rules.add(Rule {
+            id: 0,
+            enabled: false,
+            conditions: vec![
+                Condition { subject: Subject::Food, comparison: Comparison::Below, value: 20.0 },
+                Condition { subject: Subject::Awake, comparison: Comparison::Is, value: 0.0 },
+            ],
+            action: Action::Feed(FoodKind::KittenFormula),
+        });
+        rules.add(Rule {
+            id: 0,
+            enabled: false,
+            conditions: vec![Condition { subject: Subject::Stock(FoodKind::DryKibble), comparison: Comparison::Below, value: 100.0 }],
+            action: Action::Buy(FoodKind::DryKibble, 500),
+        });
+        rules
+    }
+
+    /// Adds `rule` under a new id.
+    pub fn add(&mut self, mut rule: Rule) {
+        rule.id = self.next_id;
+        self.rules.push(rule);
+        self.next_id += 1;
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.rules.retain(|rule| rule.id != id);
+    }
+
+    /// Enabled rules whose conditions hold this tick, with the index of the cat they apply to.
+    /// Shelter-wide actions fire once if their cat conditions, if any, hold for at least one cat.
+    pub fn due(&self, cats: &[CatInfo], inventory: &Inventory) -> Vec<(usize, Option<usize>)> {
+        let mut due = vec![];
+        for (index, rule) in self.rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let holds = |cat: Option<&CatInfo>| rule.conditions.iter().all(|condition| condition.holds(cat, inventory));
+            if rule.action.is_for_cat() {
+                due.extend((0..cats.len()).filter(|cat| holds(Some(&cats[*cat]))).map(|cat| (index, Some(cat))));
+            } else if rule.conditions.iter().any(|condition| condition.subject.is_about_cat()) {
+                if cats.iter().any(|cat| holds(Some(cat))) {
+                    due.push((index, None));
+                }
+            } else if holds(None) {
+                due.push((index, None));
+            }
+        }
+        due
+    }
+
+    pub fn record(&mut self, date: NaiveDateTime, text: String) {
+        if self.log.len() >= LOG_LEN {
+            self.log.pop_front();
+        }
+        self.log.push_back((date, text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn cats(food: [f32; 3]) -> Vec<CatInfo> {
+        food.iter().map(|food| {
+            let mut cat = CatInfo::new_cat(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+            cat.food = *food;
+            cat.sleep = false;
+            cat
+        }).collect()
+    }
+
+    fn rule(conditions: Vec<Condition>, action: Action) -> Rule {
+        Rule { id: 0, enabled: true, conditions, action }
+    }
+
+    fn hungry() -> Condition {
+        Condition { subject: Subject::Food, comparison: Comparison::Below, value: 20.0 }
+    }
+
+    #[test]
+    fn cat_rules_fire_for_each_matching_cat() {
+        let mut rules = Rules { rules: vec![], log: VecDeque::new(), next_id: 0 };
+        rules.add(rule(vec![hungry()], Action::Groom));
+        let inventory = Inventory::get_inventory();
+        assert_eq!(rules.due(&cats([10.0, 50.0, 5.0]), &inventory), vec![(0, Some(0)), (0, Some(2))]);
+        assert!(rules.due(&cats([30.0, 50.0, 25.0]), &inventory).is_empty());
+    }
+
+    #[test]
+    fn shelter_rules_fire_once() {
+        let mut rules = Rules { rules: vec![], log: VecDeque::new(), next_id: 0 };
+        rules.add(rule(vec![hungry()], Action::Buy(FoodKind::DryKibble, 100)));
+        rules.add(rule(vec![Condition { subject: Subject::Stock(FoodKind::DryKibble), comparison: Comparison::Below, value: 10.0 }], Action::Buy(FoodKind::DryKibble, 100)));
+        let mut inventory = Inventory::get_inventory();
+        assert_eq!(rules.due(&cats([10.0, 5.0, 50.0]), &inventory), vec![(0, None), (1, None)]);
+
+        inventory.fill_inventory(FoodKind::DryKibble, 10, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(rules.due(&cats([50.0, 50.0, 50.0]), &inventory), vec![]);
+    }
+
+    #[test]
+    fn disabled_rules_never_fire() {
+        let rules = Rules::new();
+        assert!(rules.rules.iter().all(|rule| !rule.enabled));
+        assert!(rules.due(&cats([0.0, 0.0, 0.0]), &Inventory::get_inventory()).is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::str::{FromStr, SplitWhitespace};
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::inventory::FoodKind;
+use crate::rules::{Action, Comparison, Condition, Rule, Subject};
+use crate::shop::VetService;
+use crate::staff::Shift;
+use crate::toy::ToyKind;
+
+/// File every session is recorded to, replayed with `--replay session.log`.
+pub const SESSION_LOG: &str = "session.log";
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// A player action, as written to the session log and fed back to the simulation on replay.
+/// Cats, loans, adopters, staff, volunteers and rules are referred to by id, kinds by their index.
+#[derive(Clone)]
+pub enum Input {
+    Feed(u64, FoodKind),
+    Play(u64, ToyKind),
+    Groom(u64),
+    Pet(u64),
+    Rest(u64),
+    Rename(u64, String),
+    Mate(u64, u64),
+    SpawnCat,
+    AgeCats,
+    AddMoney,
+    AddFood(FoodKind),
+    /// Index of the offer in the shop.
+    Buy(usize),
+    Vet(VetService, u64),
+    Clean(usize),
+    Move(u64, usize),
+    /// Index of the loan offer.
+    Borrow(usize),
+    Repay(u32),
+    Adopt(u32, u64),
+    Hire(u32),
+    Fire(u32),
+    SetShift(u32, Shift),
+    /// Staff member and the room they look after, or the whole shelter.
+    Assign(u32, Option<usize>),
+    Recruit,
+    Dismiss(u32),
+    AddRule(Rule),
+    ToggleRule(u32),
+    RemoveRule(u32),
+    DiscardSpoiled,
+    Undo,
+    Redo,
+}
+
+/// A name as typed by the player, fit for a session log line: pasted text can hold line
+/// breaks, which would split it.
+pub fn clean_name(name: &str) -> String {
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    name.trim().to_string()
+}
+
+fn index<T: PartialEq>(all: &[T], value: &T) -> usize {
+    all.iter().position(|other| other == value).expect("value listed in its enum")
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Feed(cat, kind) => write!(f, "feed {} {}", cat, index(&FoodKind::ALL, kind)),
+            Input::Play(cat, kind) => write!(f, "play {} {}", cat, index(&ToyKind::ALL, kind)),
+            Input::Groom(cat) => write!(f, "groom {}", cat),
+            Input::Pet(cat) => write!(f, "pet {}", cat),
+            Input::Rest(cat) => write!(f, "rest {}", cat),
+            Input::Rename(cat, name) => write!(f, "rename {} {}", cat, name),
+            Input::Mate(cat, mate) => write!(f, "mate {} {}", cat, mate),
+            Input::SpawnCat => write!(f, "spawn"),
+            Input::AgeCats => write!(f, "age"),
+            Input::AddMoney => write!(f, "add-money"),
+            Input::AddFood(kind) => write!(f, "add-food {}", index(&FoodKind::ALL, kind)),
+            Input::Buy(offer) => write!(f, "buy {}", offer),
+            Input::Vet(service, cat) => write!(f, "vet {} {}", index(&VetService::ALL, service), cat),
+            Input::Clean(room) => write!(f, "clean {}", room),
+            Input::Move(cat, room) => write!(f, "move {} {}", cat, room),
+            Input::Borrow(offer) => write!(f, "borrow {}", offer),
+            Input::Repay(loan) => write!(f, "repay {}", loan),
+            Input::Adopt(adopter, cat) => write!(f, "adopt {} {}", adopter, cat),
+            Input::Hire(candidate) => write!(f, "hire {}", candidate),
+            Input::Fire(member) => write!(f, "fire {}", member),
+            Input::SetShift(member, shift) => write!(f, "shift {} {}", member, index(&Shift::ALL, shift)),
+            Input::Assign(member, Some(room)) => write!(f, "assign {} {}", member, room),
+            Input::Assign(member, None) => write!(f, "assign {} -", member),
+            Input::Recruit => write!(f, "recruit"),
+            Input::Dismiss(volunteer) => write!(f, "dismiss {}", volunteer),
+            Input::AddRule(rule) => {
+                match rule.action {
+                    Action::Feed(kind) => write!(f, "add-rule feed {}", index(&FoodKind::ALL, &kind))?,
+                    Action::Play(kind) => write!(f, "add-rule play {}", index(&ToyKind::ALL, &kind))?,
+                    Action::Groom => write!(f, "add-rule groom")?,
+                    Action::Buy(kind, quantity) => write!(f, "add-rule buy {} {}", index(&FoodKind::ALL, &kind), quantity)?,
+                }
+                for condition in &rule.conditions {
+                    let subject = index(&Subject::all(), &condition.subject);
+                    write!(f, " if {} {} {}", subject, index(&Comparison::ALL, &condition.comparison), condition.value)?;
+                }
+                Ok(())
+            }
+            Input::ToggleRule(rule) => write!(f, "toggle-rule {}", rule),
+            Input::RemoveRule(rule) => write!(f, "remove-rule {}", rule),
+            Input::DiscardSpoiled => write!(f, "discard"),
+            Input::Undo => write!(f, "undo"),
+            Input::Redo => write!(f, "redo"),
+        }
+    }
+}
+
+struct Tokens<'a>(SplitWhitespace<'a>);
+
+impl Tokens<'_> {
+    fn value<T: FromStr>(&mut self) -> Result<T, String> {
+        let token = self.0.next().ok_or("missing value")?;
+        token.parse().map_err(|_| format!("invalid value '{}'", token))
+    }
+
+    /// The entry of `all` at the index read next.
+    fn pick<T: Copy>(&mut self, all: &[T]) -> Result<T, String> {
+        let index: usize = self.value()?;
+        all.get(index).copied().ok_or(format!("no choice #{}", index))
+    }
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if keyword == "rename" {
+            let (cat, name) = rest.split_once(' ').ok_or("missing name")?;
+            return Ok(Input::Rename(Tokens(cat.split_whitespace()).value()?, name.to_string()));
+        }
+
+        let mut tokens = Tokens(rest.split_whitespace());
+        let input = match keyword {
+            "feed" => Input::Feed(tokens.value()?, tokens.pick(&FoodKind::ALL)?),
+            "play" => Input::Play(tokens.value()?, tokens.pick(&ToyKind::ALL)?),
+            "groom" => Input::Groom(tokens.value()?),
+            "pet" => Input::Pet(tokens.value()?),
+            "rest" => Input::Rest(tokens.value()?),
+            "mate" => Input::Mate(tokens.value()?, tokens.value()?),
+            "spawn" => Input::SpawnCat,
+            "age" => Input::AgeCats,
+            "add-money" => Input::AddMoney,
+            "add-food" => Input::AddFood(tokens.pick(&FoodKind::ALL)?),
+            "buy" => Input::Buy(tokens.value()?),
+            "vet" => Input::Vet(tokens.pick(&VetService::ALL)?, tokens.value()?),
+            "clean" => Input::Clean(tokens.value()?),
+            "move" => Input::Move(tokens.value()?, tokens.value()?),
+            "borrow" => Input::Borrow(tokens.value()?),
+            "repay" => Input::Repay(tokens.value()?),
+            "adopt" => Input::Adopt(tokens.value()?, tokens.value()?),
+            "hire" => Input::Hire(tokens.value()?),
+            "fire" => Input::Fire(tokens.value()?),
+            "shift" => Input::SetShift(tokens.value()?, tokens.pick(&Shift::ALL)?),
+            "assign" => {
+                let member = tokens.value()?;
+                let room = tokens.value::<String>()?;
+                Input::Assign(member, if room == "-" { None } else { Some(Tokens(room.split_whitespace()).value()?) })
+            }
+            "recruit" => Input::Recruit,
+            "dismiss" => Input::Dismiss(tokens.value()?),
+            "add-rule" => {
+                let action = match tokens.value::<String>()?.as_str() {
+                    "feed" => Action::Feed(tokens.pick(&FoodKind::ALL)?),
+                    "play" => Action::Play(tokens.pick(&ToyKind::ALL)?),
+                    "groom" => Action::Groom,
+                    "buy" => Action::Buy(tokens.pick(&FoodKind::ALL)?, tokens.value()?),
+                    other => return Err(format!("unknown rule action '{}'", other)),
+                };
+                let mut conditions = vec![];
+                while let Some(token) = tokens.0.next() {
+                    if token != "if" {
+                        return Err(format!("expected a condition, found '{}'", token));
+                    }
+                    conditions.push(Condition {
+                        subject: tokens.pick(&Subject::all())?,
+                        comparison: tokens.pick(&Comparison::ALL)?,
+                        value: tokens.value()?,
+                    });
+                }
+                Input::AddRule(Rule { id: 0, enabled: true, conditions, action })
+            }
+            "toggle-rule" => Input::ToggleRule(tokens.value()?),
+            "remove-rule" => Input::RemoveRule(tokens.value()?),
+            "discard" => Input::DiscardSpoiled,
+            "undo" => Input::Undo,
+            "redo" => Input::Redo,
+            other => return Err(format!("unknown input '{}'", other)),
+        };
+        match tokens.0.next() {
+            Some(token) => Err(format!("unexpected '{}'", token)),
+            None => Ok(input),
+        }
+    }
+}
+
+/// FNV-1a hasher for state fingerprints: unlike `DefaultHasher`, its output does not change
+/// between Rust releases, so a session replays with any toolchain.
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fingerprint {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Same size on every platform.
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+/// Appends everything needed to replay the session to its log, flushing every line
+/// so that a session cut short can still be replayed up to its last action.
+pub struct Recorder {
+    file: LineWriter<File>,
+}
+
+impl Recorder {
+    /// Starts a log with the seed, the start date and a fingerprint of the starting state.
+    pub fn create(path: &Path, seed: u64, start: NaiveDate, state: u64) -> io::Result<Self> {
+        let mut file = LineWriter::new(File::create(path)?);
+        writeln!(file, "seed {}", seed)?;
+        writeln!(file, "start {}", start)?;
+        writeln!(file, "state {:016x}", state)?;
+        Ok(Self { file })
+    }
+
+    /// Logs a player action with the simulation time it happened at and the state it left.
+    pub fn input(&mut self, tick: u64, now: NaiveDateTime, state: u64, input: &Input) -> io::Result<()> {
+        writeln!(self.file, "input {} {} {:016x} {}", tick, now.format(DATE_FORMAT), state, input)
+    }
+
+    pub fn tick(&mut self, tick: u64, now: NaiveDateTime, state: u64) -> io::Result<()> {
+        writeln!(self.file, "tick {} {} {:016x}", tick, now.format(DATE_FORMAT), state)
+    }
+}
+
+/// One logged step, with the simulation time and the fingerprint of the state after it.
+pub enum Event {
+    Input { tick: u64, now: NaiveDateTime, state: u64, input: Input },
+    Tick { tick: u64, now: NaiveDateTime, state: u64 },
+}
+
+/// A recorded session, as read back for a replay.
+pub struct Session {
+    pub seed: u64,
+    pub start: NaiveDate,
+    /// Fingerprint of the state before any tick or action.
+    pub state: u64,
+    /// Every step in order, with its line in the log.
+    pub events: Vec<(usize, Event)>,
+}
+
+impl Session {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+        let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line));
+        let seed = header(&mut lines, "seed")?;
+        let start = header(&mut lines, "start")?;
+        let state = header(&mut lines, "state")?;
+        let mut session = Self {
+            seed: seed.parse().map_err(|_| format!("Invalid seed '{}'", seed))?,
+            start: start.parse().map_err(|_| format!("Invalid start date '{}'", start))?,
+            state: fingerprint(state)?,
+            events: vec![],
+        };
+        for (number, line) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+            let event = event(line).map_err(|e| format!("Line {}: {}", number, e))?;
+            session.events.push((number, event));
+        }
+        Ok(session)
+    }
+}
+
+fn header<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>, key: &str) -> Result<&'a str, String> {
+    let (number, line) = lines.next().ok_or(format!("Missing {} line", key))?;
+    line.strip_prefix(key)
+        .and_then(|value| value.strip_prefix(' '))
+        .ok_or(format!("Line {}: expected {}", number, key))
+}
+
+fn fingerprint(text: &str) -> Result<u64, String> {
+    u64::from_str_radix(text, 16).map_err(|_| format!("invalid state '{}'", text))
+}
+
+fn event(line: &str) -> Result<Event, String> {
+    let mut parts = line.splitn(5, ' ');
+    let kind = parts.next().unwrap_or_default();
+    let mut field = || parts.next().ok_or("truncated line");
+    let tick = field()?.parse().map_err(|_| "invalid tick")?;
+    let now = NaiveDateTime::parse_from_str(field()?, DATE_FORMAT).map_err(|e| format!("invalid date: {}", e))?;
+    let state = fingerprint(field()?)?;
+    match kind {
+        "input" => Ok(Event::Input { tick, now, state, input: field()?.parse()? }),
+        "tick" => Ok(Event::Tick { tick, now, state }),
+        other => Err(format!("unknown event '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_read_back_as_written() {
+        let lines = [
+            "feed 3 1", "play 3 0", "groom 3", "pet 3", "rest 3", "rename 3 Mister  Whiskers", "mate 3 4",
+            "spawn", "age", "add-money", "add-food 2", "buy 1", "vet 0 3", "clean 2", "move 3 1",
+            "borrow 0", "repay 2", "adopt 1 3", "hire 5", "fire 5", "shift 5 1", "assign 5 2", "assign 5 -",
+            "recruit", "dismiss 1", "add-rule buy 0 100 if 0 0 20 if 1 1 5.5", "add-rule groom",
+            "toggle-rule 2", "remove-rule 2", "discard", "undo", "redo",
+        ];
+        for line in lines {
+            let input: Input = line.parse().unwrap_or_else(|e| panic!("{}: {}", line, e));
+            assert_eq!(input.to_string(), line);
+        }
+    }
+
+    #[test]
+    fn malformed_inputs_are_refused() {
+        assert!("feed 3".parse::<Input>().is_err());
+        assert!("feed 3 99".parse::<Input>().is_err());
+        assert!("pet 3 4".parse::<Input>().is_err());
+        assert!("rename 3".parse::<Input>().is_err());
+        assert!("jump".parse::<Input>().is_err());
+    }
+
+    #[test]
+    fn names_lose_control_characters() {
+        assert_eq!(clean_name("  Tom\nmy\t"), "Tommy");
+        assert_eq!(clean_name("\r\n"), "");
+        assert_eq!(clean_name("Mister Whiskers"), "Mister Whiskers");
+    }
+}
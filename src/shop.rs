@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter};
+use crate::cat::CatInfo;
+use crate::inventory::{FoodKind, Supply};
+use crate::ledger::Category;
+use crate::toy::ToyKind;
+
+#[derive(Clone, Copy)]
+pub enum Product {
+    Food(FoodKind),
+    Supply(Supply),
+    Toy(ToyKind),
+}
+
+impl Product {
+    pub fn category(&self) -> Category {
+        match self {
+            Product::Food(_) => Category::Food,
+            Product::Supply(_) | Product::Toy(_) => Category::Supplies,
+        }
+    }
+}
+
+impl Display for Product {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Product::Food(kind) => write!(f, "{}", kind),
+            Product::Supply(supply) => write!(f, "{}", supply),
+            Product::Toy(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// A pack of `quantity` units of a product sold for `price`.
+pub struct Offer {
+    pub product: Product,
+    pub quantity: u32,
+    pub price: u64,
+}
+
+pub const OFFERS: [Offer; 14] = [
+    Offer { product: Product::Food(FoodKind::DryKibble), quantity: 100, price: 60 },
+    Offer { product: Product::Food(FoodKind::WetPouch), quantity: 50, price: 55 },
+    Offer { product: Product::Food(FoodKind::KittenFormula), quantity: 50, price: 80 },
+    Offer { product: Product::Food(FoodKind::SeniorDiet), quantity: 50, price: 70 },
+    Offer { product: Product::Food(FoodKind::RenalDiet), quantity: 30, price: 90 },
+    Offer { product: Product::Food(FoodKind::Treat), quantity: 100, price: 25 },
+    Offer { product: Product::Supply(Supply::Litter), quantity: 20, price: 30 },
+    Offer { product: Product::Supply(Supply::Brush), quantity: 1, price: 15 },
+    Offer { product: Product::Supply(Supply::Detangler), quantity: 10, price: 25 },
+    Offer { product: Product::Toy(ToyKind::Wand), quantity: 2, price: 15 },
+    Offer { product: Product::Toy(ToyKind::Laser), quantity: 1, price: 20 },
+    Offer { product: Product::Toy(ToyKind::Ball), quantity: 5, price: 10 },
+    Offer { product: Product::Toy(ToyKind::ScratchingPost), quantity: 1, price: 45 },
+    Offer { product: Product::Toy(ToyKind::Tunnel), quantity: 1, price: 35 },
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VetService {
+    Checkup,
+    Treatment,
+}
+
+impl VetService {
+    pub const ALL: [VetService; 2] = [VetService::Checkup, VetService::Treatment];
+
+    pub fn price(&self) -> u64 {
+        match self {
+            VetService::Checkup => 50,
+            VetService::Treatment => 150,
+        }
+    }
+
+    pub fn apply(&self, cat: &mut CatInfo) -> String {
+        match self {
+            VetService::Checkup => {
+                cat.health = (cat.health + 10.0).min(100.0);
+                format!("{} a vu le vétérinaire. Santé: {:.1}", cat.name, cat.health)
+            }
+            VetService::Treatment => {
+                cat.sick = false;
+                cat.health = (cat.health + 30.0).min(100.0);
+                format!("{} a été soigné. Santé: {:.1}", cat.name, cat.health)
+            }
+        }
+    }
+}
+
+impl Display for VetService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VetService::Checkup => write!(f, "Vet checkup"),
+            VetService::Treatment => write!(f, "Vet treatment"),
+        }
+    }
+}
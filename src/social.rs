@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::cat::CatInfo;
+use crate::personality::Personality;
+
+/// Affinity from which two cats are friends.
+pub const FRIENDS: f32 = 30.0;
+/// Affinity from which two cats are bonded and should be adopted together.
+pub const BONDED: f32 = 70.0;
+/// Affinity under which two cats are in conflict and may fight.
+pub const CONFLICT: f32 = -30.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Bonded,
+    Friends,
+    Neutral,
+    Conflict,
+}
+
+impl Relation {
+    pub fn from_affinity(affinity: f32) -> Self {
+        if affinity >= BONDED {
+            Relation::Bonded
+        } else if affinity >= FRIENDS {
+            Relation::Friends
+        } else if affinity <= CONFLICT {
+            Relation::Conflict
+        } else {
+            Relation::Neutral
+        }
+    }
+}
+
+impl Display for Relation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Relation::Bonded => write!(f, "Inséparables"),
+            Relation::Friends => write!(f, "Amis"),
+            Relation::Neutral => write!(f, "Neutre"),
+            Relation::Conflict => write!(f, "En conflit"),
+        }
+    }
+}
+
+fn key(a: u64, b: u64) -> (u64, u64) {
+    (a.min(b), a.max(b))
+}
+
+/// How much two cats like each other, from -100 to 100, for every pair that ever shared a room.
+pub struct Relationships {
+    affinity: BTreeMap<(u64, u64), f32>,
+}
+
+impl Relationships {
+    pub fn new() -> Self {
+        Self {
+            affinity: BTreeMap::new(),
+        }
+    }
+
+    pub fn affinity(&self, a: u64, b: u64) -> f32 {
+        self.affinity.get(&key(a, b)).copied().unwrap_or(0.0)
+    }
+
+    /// Every cat `cat_id` has an affinity with, and that affinity.
+    pub fn of(&self, cat_id: u64) -> Vec<(u64, f32)> {
+        self.affinity
+            .iter()
+            .filter_map(|((a, b), affinity)| match (*a == cat_id, *b == cat_id) {
+                (true, _) => Some((*b, *affinity)),
+                (_, true) => Some((*a, *affinity)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn bonded_with(&self, cat_id: u64) -> Vec<u64> {
+        self.of(cat_id).into_iter().filter(|(_, affinity)| *affinity >= BONDED).map(|(id, _)| id).collect()
+    }
+
+    pub fn cat_left(&mut self, cat_id: u64) {
+        self.affinity.retain(|(a, b), _| *a != cat_id && *b != cat_id);
+    }
+
+    /// Lets every pair of roommates get along or clash for one tick and returns what happened.
+    pub fn update(&mut self, cats: &mut [CatInfo]) -> Vec<String> {
+        let mut rng = sim_rng();
+        let mut events = vec![];
+        for i in 0..cats.len() {
+            for j in i + 1..cats.len() {
+                if cats[i].room != cats[j].room || cats[i].sleep || cats[j].sleep {
+                    continue;
+                }
+                let before = self.affinity(cats[i].id, cats[j].id);
+                let delta = rng.gen_range(-1.0..2.0) + compatibility(&cats[i], &cats[j]);
+                let after = (before + delta).clamp(-100.0, 100.0);
+                self.affinity.insert(key(cats[i].id, cats[j].id), after);
+
+                let relation = Relation::from_affinity(after);
+                if relation == Relation::Bonded && Relation::from_affinity(before) != Relation::Bonded {
+                    events.push(format!("{} et {} sont maintenant inséparables", cats[i].name, cats[j].name));
+                }
+                match relation {
+                    Relation::Friends | Relation::Bonded => {
+                        for index in [i, j] {
+                            cats[index].stress = (cats[index].stress - 1.0).max(0.0);
+                        }
+                    }
+                    Relation::Conflict if rng.gen_bool(0.1) => {
+                        for index in [i, j] {
+                            cats[index].stress = (cats[index].stress + 10.0).min(100.0);
+                        }
+                        let hurt = if rng.gen_bool(0.5) { i } else { j };
+                        cats[hurt].health = (cats[hurt].health - rng.gen_range(5.0..15.0)).max(0.0);
+                        events.push(format!("{} et {} se sont battus, {} est blessé(e)", cats[i].name, cats[j].name, cats[hurt].name));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Affinity change per tick driven by the personalities and moods of both cats.
+fn compatibility(a: &CatInfo, b: &CatInfo) -> f32 {
+    let mut delta = ((a.mood + b.mood) / 2.0 - 50.0) / 50.0;
+    for cat in [a, b] {
+        for personality in &cat.personality {
+            delta += match personality {
+                Personality::Cuddly | Personality::Playful => 0.5,
+                Personality::Lazy => 0.0,
+                Personality::Shy => -0.5,
+                Personality::Aggressive => -2.0,
+            };
+        }
+    }
+    delta
+}
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::cat::{CatInfo, MATTED_COAT};
+use crate::inventory::{FoodKind, Inventory, Supply};
+use crate::room::{self, Room};
+use crate::shop::VetService;
+
+const STAFF_NAMES: [&str; 12] = [
+    "Julie", "Thomas", "Camille", "Nicolas", "Léa", "Antoine",
+    "Manon", "Hugo", "Chloé", "Lucas", "Sarah", "Maxime",
+];
+
+/// Candidates waiting to be hired at any time.
+pub const CANDIDATES: usize = 3;
+/// Staff actions kept in the log.
+pub const LOG_LEN: usize = 200;
+/// Food level under which a caretaker feeds a cat.
+pub const HUNGRY: f32 = 40.0;
+/// Health under which a vet gives a cat a checkup.
+pub const CHECKUP_HEALTH: f32 = 50.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Caretaker,
+    Vet,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Caretaker => write!(f, "Caretaker"),
+            Role::Vet => write!(f, "Vet"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+    Morning,
+    Afternoon,
+    Night,
+}
+
+impl Shift {
+    pub const ALL: [Shift; 3] = [Shift::Morning, Shift::Afternoon, Shift::Night];
+
+    pub fn covers(&self, date: NaiveDateTime) -> bool {
+        match self {
+            Shift::Morning => (6..14).contains(&date.hour()),
+            Shift::Afternoon => (14..22).contains(&date.hour()),
+            Shift::Night => !(6..22).contains(&date.hour()),
+        }
+    }
+}
+
+impl Display for Shift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shift::Morning => write!(f, "Morning (6h-14h)"),
+            Shift::Afternoon => write!(f, "Afternoon (14h-22h)"),
+            Shift::Night => write!(f, "Night (22h-6h)"),
+        }
+    }
+}
+
+pub struct StaffMember {
+    pub id: u32,
+    pub name: &'static str,
+    pub role: Role,
+    /// From 1 (beginner) to 5 (expert): more tasks per tick, and better treatments for vets.
+    pub skill: u8,
+    pub shift: Shift,
+    /// Room the member looks after, or the whole shelter.
+    pub room: Option<usize>,
+}
+
+impl StaffMember {
+    pub fn daily_salary(&self) -> u64 {
+        match self.role {
+            Role::Caretaker => 15 + 5 * self.skill as u64,
+            Role::Vet => 50 + 15 * self.skill as u64,
+        }
+    }
+
+    fn works_in(&self, room: usize) -> bool {
+        self.room.is_none_or(|assigned| assigned == room)
+    }
+}
+
+impl Display for StaffMember {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, skill {})", self.name, self.role, self.skill)
+    }
+}
+
+/// What a staff member did during one tick.
+pub struct Work {
+    pub log: Vec<String>,
+    pub food_used: u32,
+}
+
+pub struct Staff {
+    pub members: Vec<StaffMember>,
+    pub candidates: Vec<StaffMember>,
+    pub log: VecDeque<(NaiveDateTime, String)>,
+    next_id: u32,
+}
+
+impl Staff {
+    pub fn new() -> Self {
+        let mut staff = Self {
+            members: vec![],
+            candidates: vec![],
+            log: VecDeque::new(),
+            next_id: 1,
+        };
+        for _ in 0..CANDIDATES {
+            staff.add_candidate();
+        }
+        staff
+    }
+
+    fn add_candidate(&mut self) {
+        let mut rng = sim_rng();
+        self.candidates.push(StaffMember {
+            id: self.next_id,
+            name: STAFF_NAMES[rng.gen_range(0..STAFF_NAMES.len())],
+            role: if rng.gen_bool(0.7) { Role::Caretaker } else { Role::Vet },
+            skill: rng.gen_range(1..=5),
+            shift: Shift::ALL[rng.gen_range(0..Shift::ALL.len())],
+            room: None,
+        });
+        self.next_id += 1;
+    }
+
+    /// Hires the candidate `id` and brings in a new candidate in their place.
+    pub fn hire(&mut self, id: u32) -> Option<&StaffMember> {
+        let index = self.candidates.iter().position(|candidate| candidate.id == id)?;
+        self.members.push(self.candidates.remove(index));
+        self.add_candidate();
+        self.members.last()
+    }
+
+    pub fn fire(&mut self, id: u32) -> Option<StaffMember> {
+        let index = self.members.iter().position(|member| member.id == id)?;
+        Some(self.members.remove(index))
+    }
+
+    pub fn daily_salaries(&self) -> u64 {
+        self.members.iter().map(StaffMember::daily_salary).sum()
+    }
+
+    pub fn record(&mut self, date: NaiveDateTime, text: String) {
+        if self.log.len() >= LOG_LEN {
+            self.log.pop_front();
+        }
+        self.log.push_back((date, text));
+    }
+}
+
+/// Lets `member` handle up to two tasks per skill level in their rooms, most urgent first.
+pub fn work(member: &StaffMember, cats: &mut [CatInfo], rooms: &mut [Room], inventory: &mut Inventory, today: NaiveDate) -> Work {
+    let mut work = Work { log: vec![], food_used: 0 };
+    let mut tasks = member.skill as usize * 2;
+    match member.role {
+        Role::Caretaker => {
+            for cat in cats.iter_mut().filter(|cat| member.works_in(cat.room) && !cat.sleep && cat.food < HUNGRY) {
+                if tasks == 0 {
+                    return work;
+                }
+                let Some(kind) = FoodKind::ALL.into_iter().find(|kind| {
+                    inventory.count(*kind) > 0 && !inventory.next_spoiled(*kind, today) && kind.unsuitable_for(cat).is_none()
+                }) else {
+                    work.log.push(format!("No suitable food for {}", cat.name));
+                    continue;
+                };
+                let stock = inventory.total();
+                let (message, _) = cat.feed(kind, inventory, today);
+                let eaten = stock - inventory.total();
+                work.food_used += eaten;
+                rooms[cat.room].soil(room::LITTER_PER_MEAL * eaten as f32);
+                work.log.push(message);
+                tasks -= 1;
+            }
+            for room in rooms.iter_mut().filter(|room| member.works_in(room.id) && room.is_dirty()) {
+                if tasks == 0 {
+                    return work;
+                }
+                if !inventory.take_supply(Supply::Litter, 1) {
+                    work.log.push(format!("No {} to clean {}", Supply::Litter, room.name));
+                    break;
+                }
+                room.clean();
+                work.log.push(format!("Cleaned the litter boxes of {}", room.name));
+                tasks -= 1;
+            }
+            for cat in cats.iter_mut().filter(|cat| member.works_in(cat.room) && !cat.sleep && cat.coat < MATTED_COAT) {
+                if tasks == 0 || inventory.supply_count(Supply::Brush) == 0 {
+                    return work;
+                }
+                work.log.push(cat.groom(inventory).0);
+                tasks -= 1;
+            }
+        }
+        Role::Vet => {
+            let mut rng = sim_rng();
+            for cat in cats.iter_mut().filter(|cat| member.works_in(cat.room) && (cat.sick || cat.health < CHECKUP_HEALTH)) {
+                if tasks == 0 {
+                    return work;
+                }
+                if !cat.sick {
+                    work.log.push(VetService::Checkup.apply(cat));
+                } else if rng.gen_bool(0.4 + 0.12 * member.skill as f64) {
+                    work.log.push(VetService::Treatment.apply(cat));
+                } else {
+                    work.log.push(format!("Could not cure {}", cat.name));
+                }
+                tasks -= 1;
+            }
+        }
+    }
+    work
+}
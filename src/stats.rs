@@ -0,0 +1,124 @@
+use std::collections::{BTreeMap, VecDeque};
+use chrono::{NaiveDate, NaiveDateTime};
+use crate::calendar::{month_key, HOURS_PER_TICK};
+use crate::cat::CatInfo;
+
+/// Number of simulation ticks kept for the time series of the dashboard.
+pub const SAMPLES_LEN: usize = 1000;
+
+#[derive(Default, Clone, Copy)]
+pub struct MonthStats {
+    pub births: u32,
+    pub deaths: u32,
+    pub adoptions: u32,
+    pub intakes: u32,
+    pub food_used: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct Sample {
+    pub date: NaiveDateTime,
+    pub money: i64,
+    pub food_stock: u32,
+    pub food_used: u64,
+}
+
+pub struct ShelterStats {
+    pub months: BTreeMap<(i32, u32), MonthStats>,
+    pub samples: VecDeque<Sample>,
+    food_used: u64,
+}
+
+impl ShelterStats {
+    pub fn new() -> Self {
+        Self {
+            months: BTreeMap::new(),
+            samples: VecDeque::new(),
+            food_used: 0,
+        }
+    }
+
+    pub fn month(&mut self, date: NaiveDate) -> &mut MonthStats {
+        self.months.entry(month_key(date)).or_default()
+    }
+
+    pub fn record_food_used(&mut self, date: NaiveDate, amount: u32) {
+        self.food_used += amount as u64;
+        self.month(date).food_used += amount;
+    }
+
+    /// Takes back portions counted by `record_food_used`, when a meal is undone.
+    pub fn cancel_food_used(&mut self, date: NaiveDate, amount: u32) {
+        self.food_used = self.food_used.saturating_sub(amount as u64);
+        let month = self.month(date);
+        month.food_used = month.food_used.saturating_sub(amount);
+    }
+
+    pub fn sample(&mut self, date: NaiveDateTime, money: i64, food_stock: u32) {
+        if self.samples.len() >= SAMPLES_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { date, money, food_stock, food_used: self.food_used });
+    }
+
+    /// Food items used per simulated day, averaged over the last 30 simulated days.
+    pub fn food_rate(&self) -> f32 {
+        let window = (30 * 24 / HOURS_PER_TICK) as usize;
+        let start = self.samples.len().saturating_sub(window);
+        match (self.samples.get(start), self.samples.back()) {
+            (Some(first), Some(last)) if last.date > first.date => {
+                let days = (last.date - first.date).num_hours() as f32 / 24.0;
+                (last.food_used - first.food_used) as f32 / days
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+pub struct Population {
+    pub total: usize,
+    pub by_race: BTreeMap<String, usize>,
+    pub by_color: BTreeMap<String, usize>,
+    pub by_gender: BTreeMap<String, usize>,
+    pub by_age: Vec<(&'static str, usize)>,
+    pub avg_health: f32,
+    pub avg_food: f32,
+}
+
+pub const AGE_BANDS: [(&str, u8, u8); 4] = [
+    ("Kitten (0-1)", 0, 1),
+    ("Young (2-6)", 2, 6),
+    ("Adult (7-10)", 7, 10),
+    ("Senior (11+)", 11, u8::MAX),
+];
+
+impl Population {
+    pub fn from_cats(cats: &[CatInfo]) -> Self {
+        let mut population = Self {
+            total: cats.len(),
+            by_race: BTreeMap::new(),
+            by_color: BTreeMap::new(),
+            by_gender: BTreeMap::new(),
+            by_age: AGE_BANDS.iter().map(|(name, _, _)| (*name, 0)).collect(),
+            avg_health: 0.0,
+            avg_food: 0.0,
+        };
+
+        for cat in cats {
+            *population.by_race.entry(cat.race.to_string()).or_default() += 1;
+            *population.by_color.entry(cat.color_type.to_string()).or_default() += 1;
+            *population.by_gender.entry(cat.gender.to_string()).or_default() += 1;
+            if let Some(band) = AGE_BANDS.iter().position(|(_, min, max)| (*min..=*max).contains(&cat.age)) {
+                population.by_age[band].1 += 1;
+            }
+            population.avg_health += cat.health;
+            population.avg_food += cat.food;
+        }
+
+        if !cats.is_empty() {
+            population.avg_health /= cats.len() as f32;
+            population.avg_food /= cats.len() as f32;
+        }
+        population
+    }
+}
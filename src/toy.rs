@@ -0,0 +1,84 @@
+use std::fmt::{Display, Formatter};
+use crate::cat::CatInfo;
+use crate::personality::Personality;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToyKind {
+    Wand,
+    Laser,
+    Ball,
+    ScratchingPost,
+    Tunnel,
+}
+
+/// Effects of one play session with a toy, before the cat's preference is applied.
+pub struct ToyProfile {
+    pub mood: f32,
+    pub weight: f32,
+    pub energy: f32,
+    pub stress: f32,
+    /// Play sessions a new toy lasts before it is worn out.
+    pub durability: u32,
+}
+
+impl ToyKind {
+    pub const ALL: [ToyKind; 5] = [ToyKind::Wand, ToyKind::Laser, ToyKind::Ball, ToyKind::ScratchingPost, ToyKind::Tunnel];
+
+    pub fn profile(&self) -> ToyProfile {
+        match self {
+            ToyKind::Wand => ToyProfile { mood: 12.0, weight: 0.06, energy: 18.0, stress: 2.0, durability: 15 },
+            ToyKind::Laser => ToyProfile { mood: 8.0, weight: 0.08, energy: 22.0, stress: 0.0, durability: 40 },
+            ToyKind::Ball => ToyProfile { mood: 6.0, weight: 0.04, energy: 12.0, stress: 1.0, durability: 25 },
+            ToyKind::ScratchingPost => ToyProfile { mood: 6.0, weight: 0.01, energy: 5.0, stress: 8.0, durability: 60 },
+            ToyKind::Tunnel => ToyProfile { mood: 8.0, weight: 0.03, energy: 10.0, stress: 5.0, durability: 30 },
+        }
+    }
+
+    /// How much `cat` enjoys this toy, from 0.2 (ignores it) to 2.0 (loves it).
+    /// Chasing toys suit active breeds, calm ones suit placid breeds, and each trait has its favourites.
+    pub fn preference(&self, cat: &CatInfo) -> f32 {
+        let activity = cat.race.activity_level() as f32;
+        let mut preference = match self {
+            ToyKind::Wand | ToyKind::Laser | ToyKind::Ball => 0.5 + activity * 0.2,
+            ToyKind::ScratchingPost | ToyKind::Tunnel => 1.7 - activity * 0.2,
+        };
+        for personality in &cat.personality {
+            preference += match (personality, self) {
+                (Personality::Playful, ToyKind::Wand | ToyKind::Laser | ToyKind::Ball) => 0.4,
+                (Personality::Lazy, ToyKind::Laser | ToyKind::Ball) => -0.4,
+                (Personality::Lazy, ToyKind::ScratchingPost) => 0.3,
+                (Personality::Shy, ToyKind::Tunnel) => 0.6,
+                (Personality::Shy, ToyKind::Wand) => -0.3,
+                (Personality::Cuddly, ToyKind::Wand) => 0.3,
+                (Personality::Aggressive, ToyKind::ScratchingPost) => 0.5,
+                _ => 0.0,
+            };
+        }
+        preference.clamp(0.2, 2.0)
+    }
+}
+
+impl Display for ToyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToyKind::Wand => write!(f, "Feather wand"),
+            ToyKind::Laser => write!(f, "Laser pointer"),
+            ToyKind::Ball => write!(f, "Ball"),
+            ToyKind::ScratchingPost => write!(f, "Scratching post"),
+            ToyKind::Tunnel => write!(f, "Tunnel"),
+        }
+    }
+}
+
+/// A toy in the inventory, worn a little by every play session.
+#[derive(Clone)]
+pub struct Toy {
+    pub kind: ToyKind,
+    pub durability: u32,
+}
+
+impl Toy {
+    pub fn new(kind: ToyKind) -> Self {
+        Self { kind, durability: kind.profile().durability }
+    }
+}
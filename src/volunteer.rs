@@ -0,0 +1,161 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use rand::Rng;
+use crate::rng::sim_rng;
+use crate::cat::{CatInfo, PLAY_ENERGY};
+use crate::inventory::Inventory;
+use crate::staff::Shift;
+use crate::toy::ToyKind;
+
+const VOLUNTEER_NAMES: [&str; 12] = [
+    "Anne", "Bruno", "Claire", "David", "Emma", "François",
+    "Gabrielle", "Henri", "Inès", "Jules", "Karine", "Louis",
+];
+
+/// Largest roster the shelter can coordinate.
+pub const MAX_VOLUNTEERS: usize = 10;
+/// Cats a volunteer spends time with on every tick of a session.
+pub const CATS_PER_SESSION: usize = 3;
+/// Shifts volunteers can sign up for; nobody comes at night.
+pub const VOLUNTEER_SHIFTS: [Shift; 2] = [Shift::Morning, Shift::Afternoon];
+pub const WEEK: [Weekday; 7] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+
+pub struct Volunteer {
+    pub id: u32,
+    pub name: &'static str,
+    /// Weekly slots the volunteer signed up for.
+    pub availability: Vec<(Weekday, Shift)>,
+    /// Chance of actually showing up to a slot.
+    pub reliability: f64,
+    pub sessions: u32,
+    pub no_shows: u32,
+    /// The last slot attendance was decided for, and whether the volunteer came.
+    attending: Option<(NaiveDate, Shift, bool)>,
+}
+
+impl Volunteer {
+    fn random(id: u32) -> Self {
+        let mut rng = sim_rng();
+        let mut availability = vec![];
+        for _ in 0..rng.gen_range(1..=3) {
+            let slot = (WEEK[rng.gen_range(0..WEEK.len())], VOLUNTEER_SHIFTS[rng.gen_range(0..VOLUNTEER_SHIFTS.len())]);
+            if !availability.contains(&slot) {
+                availability.push(slot);
+            }
+        }
+        Self {
+            id,
+            name: VOLUNTEER_NAMES[rng.gen_range(0..VOLUNTEER_NAMES.len())],
+            availability,
+            reliability: rng.gen_range(0.5..1.0),
+            sessions: 0,
+            no_shows: 0,
+            attending: None,
+        }
+    }
+
+    pub fn is_available(&self, weekday: Weekday, shift: Shift) -> bool {
+        self.availability.contains(&(weekday, shift))
+    }
+
+    /// Share of slots the volunteer actually came to so far.
+    pub fn attendance(&self) -> Option<f32> {
+        let slots = self.sessions + self.no_shows;
+        (slots > 0).then(|| self.sessions as f32 / slots as f32)
+    }
+
+    /// Whether the volunteer is at the shelter at `now`, rolling once per slot whether they show up.
+    fn attend(&mut self, now: NaiveDateTime) -> Attendance {
+        let Some(shift) = VOLUNTEER_SHIFTS.into_iter().find(|shift| shift.covers(now)) else {
+            return Attendance::Off;
+        };
+        if !self.is_available(now.weekday(), shift) {
+            return Attendance::Off;
+        }
+        if let Some((date, slot, came)) = self.attending {
+            if date == now.date() && slot == shift {
+                return if came { Attendance::Present } else { Attendance::Off };
+            }
+        }
+        let came = sim_rng().gen_bool(self.reliability);
+        self.attending = Some((now.date(), shift, came));
+        if came {
+            self.sessions += 1;
+            Attendance::Present
+        } else {
+            self.no_shows += 1;
+            Attendance::NoShow
+        }
+    }
+}
+
+enum Attendance {
+    Off,
+    NoShow,
+    Present,
+}
+
+pub struct Volunteers {
+    pub roster: Vec<Volunteer>,
+    next_id: u32,
+}
+
+impl Volunteers {
+    pub fn new() -> Self {
+        let mut volunteers = Self { roster: vec![], next_id: 1 };
+        for _ in 0..3 {
+            volunteers.recruit();
+        }
+        volunteers
+    }
+
+    pub fn recruit(&mut self) -> Option<&Volunteer> {
+        if self.roster.len() >= MAX_VOLUNTEERS {
+            return None;
+        }
+        self.roster.push(Volunteer::random(self.next_id));
+        self.next_id += 1;
+        self.roster.last()
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.roster.retain(|volunteer| volunteer.id != id);
+    }
+
+    /// Volunteers signed up for a weekly slot; an empty list is a coverage gap.
+    pub fn coverage(&self, weekday: Weekday, shift: Shift) -> Vec<&'static str> {
+        self.roster.iter().filter(|volunteer| volunteer.is_available(weekday, shift)).map(|volunteer| volunteer.name).collect()
+    }
+
+    /// Lets every volunteer present at `now` play with or keep company to the loneliest cats,
+    /// and returns what happened, including no-shows.
+    pub fn update(&mut self, now: NaiveDateTime, cats: &mut [CatInfo], inventory: &mut Inventory) -> Vec<String> {
+        let mut log = vec![];
+        for volunteer in self.roster.iter_mut() {
+            match volunteer.attend(now) {
+                Attendance::Off => continue,
+                Attendance::NoShow => {
+                    log.push(format!("{} (volunteer) did not show up", volunteer.name));
+                    continue;
+                }
+                Attendance::Present => {}
+            }
+            let mut candidates: Vec<usize> = (0..cats.len())
+                .filter(|index| !cats[*index].sleep && !cats[*index].in_quarantine(now.date()))
+                .collect();
+            candidates.sort_by(|a, b| cats[*a].mood.total_cmp(&cats[*b].mood));
+            for index in candidates.into_iter().take(CATS_PER_SESSION) {
+                let cat = &mut cats[index];
+                let toy = ToyKind::ALL
+                    .into_iter()
+                    .filter(|kind| inventory.toy_count(*kind) > 0 && kind.preference(cat) >= 1.0)
+                    .max_by(|a, b| a.preference(cat).total_cmp(&b.preference(cat)));
+                let (message, _) = match toy {
+                    Some(kind) if cat.energy >= PLAY_ENERGY => cat.play(kind, inventory),
+                    _ => cat.socialise(),
+                };
+                log.push(format!("{} (volunteer): {}", volunteer.name, message));
+            }
+        }
+        log
+    }
+}